@@ -14,12 +14,38 @@ pub enum SmError {
     NoData,
     #[error("schuldmanager-online.de did not return the expected data")]
     IncorrectData,
+    #[error("could not find the bundle version in the schulmanager-online.de web app")]
+    NoBundleVersion,
     #[error("called get() on uninitialized data")]
     UninitializedData,
     #[error("Unknown Office SSO Error")]
     UnknownMS,
     #[error("Incorrect Microsoft username or password")]
     InvalidMSCredentials,
+    #[error("Microsoft account requires multi-factor authentication")]
+    MSMfaRequired,
+    #[error("Microsoft rejected the multi-factor authentication code")]
+    InvalidMSMfaCode,
+    #[error("Microsoft requires consent to the application, sign in once using a browser")]
+    MSConsentRequired,
+    #[error("Microsoft account password expired")]
+    MSPasswordExpired,
     #[error("non-valid client Id")]
-    NonvalidAppId
+    NonvalidAppId,
+    #[error("no Office 365 login found for the school")]
+    NoOfficeProvider,
+    #[error("answer for form field or survey question '{}' does not match the form", field_id)]
+    InvalidFormAnswer { field_id: usize },
+    #[error("pages have to hold at least one item")]
+    InvalidPageLimit,
+    #[error("letter '{}' has no reply form", letter_id)]
+    LetterWithoutForm { letter_id: usize },
+    #[error("absence period ends before it starts")]
+    InvalidAbsencePeriod,
+    #[error("absences can't be reported for past dates ({})", date)]
+    AbsenceInPast { date: chrono::NaiveDate },
+    #[error("absence period starts or ends on a weekend ({})", date)]
+    AbsenceOnWeekend { date: chrono::NaiveDate },
+    #[error("absence report is missing a reason")]
+    MissingAbsenceReason
 }
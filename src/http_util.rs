@@ -0,0 +1,49 @@
+/* request and response body helpers for the endpoints outside of the batched api */
+
+/* picks a boundary that does not occur inside the uploaded data */
+pub(crate) fn multipart_boundary(data: &[u8]) -> String {
+	let seed = chrono::Utc::now().timestamp_subsec_nanos() as u64;
+	(0u64..).map(|n| format!("----libschulmanager{:016x}", seed.wrapping_add(n).wrapping_mul(0x9e37_79b9_7f4a_7c15)))
+		.find(|boundary| !data.windows(boundary.len()).any(|window| window == boundary.as_bytes()))
+		.expect("a free boundary exists")
+}
+
+pub(crate) fn multipart_file(boundary: &str, field: &str, filename: &str, mime_type: &str, data: &[u8]) -> Vec<u8> {
+	/* same escaping browsers apply to file names in form-data */
+	let filename = filename.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A");
+	let mut body = format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n", boundary, field, filename, mime_type).into_bytes();
+	body.extend_from_slice(data);
+	body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+	body
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+	let mut bytes = Vec::with_capacity(value.len());
+	let mut iter = value.bytes();
+	while let Some(byte) = iter.next() {
+		if byte == b'%' {
+			let hex = [iter.next()?, iter.next()?];
+			bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+		} else {
+			bytes.push(byte);
+		}
+	}
+	String::from_utf8(bytes).ok()
+}
+
+/* prefers the RFC 5987 `filename*` parameter, which is how umlauts in filenames get transmitted */
+pub(crate) fn content_disposition_filename(value: &str) -> Option<String> {
+	let mut plain = None;
+	for param in value.split(';').map(str::trim) {
+		if let Some(encoded) = param.strip_prefix("filename*=") {
+			let (charset, rest) = encoded.split_once('\'')?;
+			let (_language, encoded) = rest.split_once('\'')?;
+			if charset.eq_ignore_ascii_case("utf-8") {
+				return percent_decode(encoded)
+			}
+		} else if let Some(name) = param.strip_prefix("filename=") {
+			plain = Some(name.trim_matches('"').to_owned());
+		}
+	}
+	plain
+}
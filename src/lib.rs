@@ -3,10 +3,26 @@ pub mod sm;
 pub mod o365;
 pub mod transformers;
 pub mod errors;
-use sm::{RequestManager, ResultBody};
+mod http_util;
+use sm::{RequestManager, ResultBody, SystemStatusMessage};
 use sm::{Timetable, TimetableResult};
 use sm::{Hours, HoursResult};
-use isahc::{prelude::*, HttpClient, cookies::CookieJar, Request};
+use sm::{Letters, Letter, ConfirmLetter, SubmitForm};
+use sm::{Subscriptions, Messages, SendMessage, MarkAsRead, Recipients};
+use sm::{Calendar, CalendarResult};
+use sm::{Exams, ExamsResult};
+use sm::{Homework, HomeworkResult};
+use sm::{SubmitSickNote, SickNotes, SickNotesResult, AbsencePeriod};
+use sm::{Absences, AbsencesResult};
+use sm::{Grades, GradesResult};
+use sm::{ConsultationEvents, ConsultationEventsResult, Slots, Bookings, BookSlot, CancelBooking};
+use sm::{Counters, CountersResult};
+use sm::{Surveys, SurveysResult, Survey, SubmitSurvey, Submissions};
+use sm::{Courses, CoursesResult, CourseEntries, Assignments, SubmitAssignment};
+use sm::{Institution, InstitutionResult};
+use sm::{Teachers, TeachersResult, Classes, StudentGroups, Directory};
+use sm::{Substitutions, SubstitutionsResult};
+use isahc::{prelude::*, HttpClient, AsyncBody, cookies::CookieJar, Request};
 use http::{header::{self, HeaderMap, HeaderValue}, method::Method};
 use anyhow::Result;
 
@@ -23,18 +39,99 @@ pub struct SmOfficeUser {
     pub password: String
 }
 
+/// Office 365 single sign-on of a school, consisting of the Schulmanager OIDC provider id
+/// (`https://login.schulmanager-online.de/oidc/<provider_id>`) and the Microsoft tenant.
+#[cfg(feature = "microsoft")]
+#[derive(Debug, Clone)]
+pub struct SmOfficeSso {
+	pub provider_id: String,
+	/// discovered from the provider's redirect to Microsoft if `None`
+	pub tenant: Option<String>
+}
+#[cfg(feature = "microsoft")]
+impl Default for SmOfficeSso {
+	/// The school this library was initially written for
+	fn default() -> Self {
+		Self::new(String::from("413")).with_tenant(String::from("82a6d564-b994-4598-aff6-e131f8cfb1ae"))
+	}
+}
+#[cfg(feature = "microsoft")]
+impl SmOfficeSso {
+	pub fn new(provider_id: String) -> Self {
+		Self {
+			provider_id,
+			tenant: None
+		}
+	}
+	pub fn with_tenant(mut self, tenant: String) -> Self {
+		self.tenant = Some(tenant);
+		self
+	}
+	/// Finds the provider id in `page`, e.g. the school's Schulmanager login page or any page
+	/// linking its Office login. The tenant gets discovered when logging in.
+	pub async fn discover(page: &str) -> Result<Self> {
+		let mut response = HttpClient::new()?.get_async(page).await?;
+		let provider_id = o365::parse_provider_id(&response.text().await?)
+			.ok_or_else(|| anyhow::Error::new(errors::SmError::NoOfficeProvider).context(format!("{} does not link an Office login", page)))?;
+		Ok(Self::new(provider_id))
+	}
+
+	async fn auth(&self) -> Result<o365::O365Auth> {
+		let url = format!("https://login.schulmanager-online.de/oidc/{}", self.provider_id);
+		match &self.tenant {
+			Some(tenant) => o365::O365Auth::new(url, tenant.clone()).await,
+			None => o365::O365Auth::discover(url).await
+		}
+	}
+
+	pub async fn login(&self, user: SmOfficeUser) -> Result<Schulmanager> {
+		let o365 = self.auth().await?;
+		o365.login(user.email, user.password).await?;
+		Schulmanager::new(ClientAuthMethod::CookieAuth(o365.req_client.cookie_jar().unwrap())).await
+	}
+
+	/// Like `login`, but asks `mfa_code` for the one-time code if the account requires a second factor.
+	pub async fn login_with_mfa<F, Fut>(&self, user: SmOfficeUser, mfa_code: F) -> Result<Schulmanager>
+	where
+		F: Fn(o365::MfaChallenge) -> Fut,
+		Fut: std::future::Future<Output = Option<String>>
+	{
+		let o365 = self.auth().await?;
+		o365.login_with_mfa(user.email, user.password, mfa_code).await?;
+		Schulmanager::new(ClientAuthMethod::CookieAuth(o365.req_client.cookie_jar().unwrap())).await
+	}
+}
+
 #[derive(Debug)]
 pub enum ClientAuthMethod<'c> {
 	CookieAuth(&'c CookieJar),
 	JwtAuth(String)
 }
 
+/// Callback receiving the system status messages of every call, see `Schulmanager::set_status_handler`
+type StatusCallback = dyn Fn(&[SystemStatusMessage]) + Send + Sync;
+pub struct StatusHandler(Box<StatusCallback>);
+impl std::fmt::Debug for StatusHandler {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("StatusHandler")
+	}
+}
+
+#[derive(Debug)]
+enum BundleVersion {
+	Static(String),
+	Discovered(std::sync::RwLock<Option<String>>)
+}
+
 #[derive(Debug)]
 pub struct Schulmanager {
     pub client: HttpClient,
     pub token: String,
     pub student_id: usize,
-    pub student_class_id: usize
+    pub student_class_id: usize,
+    pub last_seen_notification_timestamp: Option<String>,
+    status_handler: Option<StatusHandler>,
+    bundle_version: BundleVersion
 }
 impl <'l> Schulmanager {
 	pub async fn new(auth: ClientAuthMethod<'_>) -> Result<Self> {
@@ -78,33 +175,103 @@ impl <'l> Schulmanager {
         }else{
             match get_user.user {
                 Some(user) => {
-                	Ok(Schulmanager {
-                		client,
-                		token: jwt.to_str()?.to_owned(),
-                		student_id: user.associated_student.id,
-                		student_class_id: user.associated_student.class_id
-                	})
+                	let mut schulmanager = Self::from_parts(client, jwt.to_str()?.to_owned(), user.associated_student.id, user.associated_student.class_id);
+                	schulmanager.last_seen_notification_timestamp = user.last_seen_notification_timestamp;
+                	Ok(schulmanager)
                 },
                 None => Err(Box::new(errors::SmError::UnknownAuth).into())
             }
         }
 	}
 
+	/// Builds a client from an already authenticated `client` and its `token`, without any requests.
+	pub fn from_parts(client: HttpClient, token: String, student_id: usize, student_class_id: usize) -> Self {
+		Schulmanager {
+			client,
+			token,
+			student_id,
+			student_class_id,
+			last_seen_notification_timestamp: None,
+			status_handler: None,
+			bundle_version: BundleVersion::Discovered(Default::default())
+		}
+	}
+
+	/// Logs in using the Office 365 login of the default school, see `SmOfficeSso` for other schools.
 	#[cfg(feature = "microsoft")]
     pub async fn login_office(user: SmOfficeUser) -> Result<Self> {
-        let o365 = o365::O365Auth::new(String::from("https://login.schulmanager-online.de/oidc/413"), String::from("82a6d564-b994-4598-aff6-e131f8cfb1ae")).await?;
-        o365.login(user.email, user.password).await?;
-        Self::new(ClientAuthMethod::CookieAuth(o365.req_client.cookie_jar().unwrap())).await
+        SmOfficeSso::default().login(user).await
     }
 
-    #[deprecated(since = "0.2", note = "consider using Schulmanager::new directly")]
+	/// Like `login_office`, but asks `mfa_code` for the one-time code if the account requires a second factor.
+	#[cfg(feature = "microsoft")]
+    pub async fn login_office_with_mfa<F, Fut>(user: SmOfficeUser, mfa_code: F) -> Result<Self>
+    where
+        F: Fn(o365::MfaChallenge) -> Fut,
+        Fut: std::future::Future<Output = Option<String>>
+    {
+        SmOfficeSso::default().login_with_mfa(user, mfa_code).await
+    }
+
+    #[deprecated(since = "0.2.0", note = "consider using Schulmanager::new directly")]
     pub async fn use_jwt(token: String) -> Result<Self> {
         Self::new(ClientAuthMethod::JwtAuth(token)).await
     }
 
-    pub async fn make_request(&'l self, mgr: &'l mut RequestManager<'l>) -> Result<()> {
+    /// Calls `handler` with the system status messages (maintenance notices, outages, ...)
+    /// of every request, even if the request itself fails afterwards.
+    pub fn set_status_handler<F: Fn(&[SystemStatusMessage]) + Send + Sync + 'static>(&mut self, handler: F) {
+    	self.status_handler = Some(StatusHandler(Box::new(handler)));
+    }
+
+    /// Always send `version` instead of discovering the bundle version from the web app.
+    pub fn set_bundle_version(&mut self, version: String) {
+    	self.bundle_version = BundleVersion::Static(version);
+    }
+
+    /// The bundle version sent with every call; discovered once and cached afterwards.
+    /// If discovery fails, `FALLBACK_BUNDLE_VERSION` is sent and discovery is tried again on the next call.
+    pub async fn bundle_version(&self) -> String {
+    	let cache = match &self.bundle_version {
+    		BundleVersion::Static(version) => return version.clone(),
+    		BundleVersion::Discovered(cache) => cache
+    	};
+    	if let Some(version) = cache.read().expect("bundle version lock poisoned").as_ref() {
+    		return version.clone()
+    	}
+    	/* the server doesn't enforce the version yet, so don't fail calls if the web app changed its layout */
+    	match self.discover_bundle_version().await {
+    		Ok(version) => {
+    			*cache.write().expect("bundle version lock poisoned") = Some(version.clone());
+    			version
+    		},
+    		Err(_) => String::from(sm::FALLBACK_BUNDLE_VERSION)
+    	}
+    }
+
+    /// Discovers the bundle version again, replacing the cached one.
+    pub async fn refresh_bundle_version(&self) -> Result<String> {
+    	match &self.bundle_version {
+    		BundleVersion::Static(version) => Ok(version.clone()),
+    		BundleVersion::Discovered(cache) => {
+    			let version = self.discover_bundle_version().await?;
+    			*cache.write().expect("bundle version lock poisoned") = Some(version.clone());
+    			Ok(version)
+    		}
+    	}
+    }
+
+    async fn discover_bundle_version(&self) -> Result<String> {
+    	let mut response = self.client.get_async("https://login.schulmanager-online.de/").await?;
+    	if !response.status().is_success() {
+    		return Err(errors::SmError::NonvalidStatusCode { statuscode: response.status().as_u16() }.into())
+    	}
+    	sm::parse_bundle_version(&response.text().await?).ok_or_else(|| errors::SmError::NoBundleVersion.into())
+    }
+
+    async fn send_calls(&self, mgr: &RequestManager<'_>, bundle_version: &str) -> Result<String> {
     	let body: String = {
-    		let body = mgr.get_request()?;
+    		let body = mgr.get_request_with_bundle_version(bundle_version)?;
     		serde_json::to_string(&body)?
     	};
 
@@ -115,10 +282,32 @@ impl <'l> Schulmanager {
 		set_json(request.headers_mut());
 		set_jwt(request.headers_mut(), &self.token)?;
 
-		let resp: ResultBody = self.client.send_async(request).await?
-			.json().await?;
+		Ok(self.client.send_async(request).await?
+			.text().await?)
+    }
+
+    /// Sends all requests of `mgr`, see `make_request_with_status` for the system status messages sent along with them.
+    pub async fn make_request(&'l self, mgr: &'l mut RequestManager<'l>) -> Result<()> {
+    	self.make_request_with_status(mgr).await?;
+    	Ok(())
+    }
+
+    /// Sends all requests of `mgr` and returns the system status messages sent along with them.
+    ///
+    /// If the server rejects the bundle version, it gets discovered again and the call is retried once.
+    pub async fn make_request_with_status(&'l self, mgr: &'l mut RequestManager<'l>) -> Result<Vec<SystemStatusMessage>> {
+    	let mut body = self.send_calls(mgr, &self.bundle_version().await).await?;
+    	if sm::is_outdated_bundle(&body) && matches!(self.bundle_version, BundleVersion::Discovered(_)) {
+    		body = self.send_calls(mgr, &self.refresh_bundle_version().await?).await?;
+    	}
+		let mut resp: ResultBody = serde_json::from_str(&body)?;
 
-		mgr.get_results(resp)
+		let messages = std::mem::take(&mut resp.system_status_messages);
+		if let (Some(handler), false) = (&self.status_handler, messages.is_empty()) {
+			(handler.0)(&messages);
+		}
+		mgr.get_results(resp)?;
+		Ok(messages)
     }
 
 	pub async fn get_timetable(&self, week: u32, year: Option<i32>) -> Result<SmTimetable> {
@@ -143,6 +332,418 @@ impl <'l> Schulmanager {
 
 		Ok(SmHours(params.get()?))
 	}
+
+	pub async fn get_letters(&self) -> Result<Vec<sm::letters::response::LetterSummary>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Letters::new();
+		mgr.add_letters(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_letter(&self, id: usize) -> Result<sm::letters::response::Letter> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Letter::new(id);
+		mgr.add_letter(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn confirm_letter(&self, id: usize) -> Result<()> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = ConfirmLetter::new(id, self.student_id);
+		mgr.add_confirm_letter(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn submit_letter_form(&self, letter: &sm::letters::response::Letter, answers: Vec<sm::letters::response::FormFieldAnswer>) -> Result<()> {
+		let form = letter.form.as_ref().ok_or(errors::SmError::LetterWithoutForm { letter_id: letter.id })?;
+		let mut mgr = RequestManager::new();
+
+		let mut params = SubmitForm::new(letter.id, self.student_id, form, answers)?;
+		mgr.add_submit_form(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_calendar(&self, start: NaiveDate, end: NaiveDate) -> Result<CalendarResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Calendar::new(start, end);
+		mgr.add_calendar(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_exams(&self, start: NaiveDate, end: NaiveDate) -> Result<ExamsResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Exams::new(self.student_id, self.student_class_id, start, end);
+		mgr.add_exams(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_homework(&self, start: NaiveDate, end: NaiveDate) -> Result<HomeworkResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Homework::new(self.student_id, self.student_class_id, start, end);
+		mgr.add_homework(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	/// Reports the student as absent for `period`; the report is validated before anything gets sent.
+	pub async fn report_absence(&self, period: AbsencePeriod, reason: String, comment: Option<String>) -> Result<sm::sick_notes::response::SickNote> {
+		let mut mgr = RequestManager::new();
+
+		let report = sm::AbsenceReport {
+			student_id: self.student_id,
+			period,
+			reason,
+			comment
+		};
+		let mut params = SubmitSickNote::new(report, chrono::Local::now().date_naive())?;
+		mgr.add_submit_sick_note(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_sick_notes(&self) -> Result<SickNotesResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = SickNotes::new(self.student_id);
+		mgr.add_sick_notes(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_absences(&self) -> Result<AbsencesResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Absences::new(self.student_id);
+		mgr.add_absences(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_grades(&self) -> Result<GradesResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Grades::new(self.student_id);
+		mgr.add_grades(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_consultation_events(&self) -> Result<ConsultationEventsResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = ConsultationEvents::new();
+		mgr.add_consultation_events(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	/// Fetches the slots of all `teachers` for a consultation event in a single request.
+	pub async fn get_consultation_slots(&self, event_id: usize, teachers: &[usize]) -> Result<Vec<sm::consultation::response::Slot>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params: Vec<Slots> = teachers.iter().map(|teacher| Slots::new(event_id, *teacher)).collect();
+		for slots in params.iter_mut() {
+			mgr.add_slots(slots)?;
+		}
+
+		self.make_request(&mut mgr).await?;
+		let mut slots = Vec::new();
+		for mut teacher_slots in params {
+			slots.append(&mut teacher_slots.get()?);
+		}
+		Ok(slots)
+	}
+
+	pub async fn get_consultation_bookings(&self, event_id: usize) -> Result<Vec<sm::consultation::response::Booking>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Bookings::new(event_id);
+		mgr.add_bookings(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn book_consultation_slot(&self, slot_id: usize) -> Result<sm::consultation::response::Booking> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = BookSlot::new(slot_id, self.student_id);
+		mgr.add_book_slot(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn cancel_consultation_booking(&self, booking_id: usize) -> Result<()> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = CancelBooking::new(booking_id);
+		mgr.add_cancel_booking(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_unread_counters(&self) -> Result<CountersResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Counters::new();
+		mgr.add_counters(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_surveys(&self) -> Result<SurveysResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Surveys::new();
+		mgr.add_surveys(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_survey(&self, id: usize) -> Result<sm::surveys::response::Survey> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Survey::new(id);
+		mgr.add_survey(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn submit_survey(&self, survey: &sm::surveys::response::Survey, answers: Vec<sm::surveys::response::QuestionAnswer>) -> Result<()> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = SubmitSurvey::new(survey, self.student_id, answers)?;
+		mgr.add_submit_survey(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_survey_submissions(&self) -> Result<Vec<sm::surveys::response::Submission>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Submissions::new();
+		mgr.add_submissions(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_courses(&self) -> Result<CoursesResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Courses::new();
+		mgr.add_courses(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_course_entries(&self, course_id: usize) -> Result<Vec<sm::learning::response::Entry>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = CourseEntries::new(course_id);
+		mgr.add_course_entries(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_assignments(&self) -> Result<Vec<sm::learning::response::Assignment>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Assignments::new();
+		mgr.add_assignments(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	/// Uploads a single file for `assignment`; hand it in afterwards using `submit_assignment`.
+	pub async fn upload_submission_file(&self, assignment: &sm::learning::response::Assignment, filename: &str, mime_type: &str, data: &[u8]) -> Result<sm::learning::response::SubmissionFile> {
+		let boundary = http_util::multipart_boundary(data);
+		let mut request = Request::builder()
+			.method(Method::POST)
+			.uri(format!("https://login.schulmanager-online.de/api/file/learning/assignment/{}", assignment.id))
+			.header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+			.body(http_util::multipart_file(&boundary, "file", filename, mime_type, data))?;
+		set_jwt(request.headers_mut(), &self.token)?;
+
+		let mut response = self.client.send_async(request).await?;
+		if !response.status().is_success() {
+			return Err(errors::SmError::NonvalidStatusCode { statuscode: response.status().as_u16() }.into())
+		}
+		Ok(response.json().await?)
+	}
+
+	pub async fn submit_assignment(&self, assignment: &sm::learning::response::Assignment, files: &[sm::learning::response::SubmissionFile], comment: Option<String>) -> Result<sm::learning::response::Submission> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = SubmitAssignment::new(assignment.id, self.student_id, files, comment);
+		mgr.add_submit_assignment(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_institution(&self) -> Result<InstitutionResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Institution::new();
+		mgr.add_institution(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_teachers(&self) -> Result<TeachersResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Teachers::new();
+		mgr.add_teachers(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	/// Fetches teachers, classes and student groups in a single call.
+	pub async fn get_directory(&self) -> Result<Directory> {
+		let mut mgr = RequestManager::new();
+
+		let mut teachers = Teachers::new();
+		let mut classes = Classes::new();
+		let mut student_groups = StudentGroups::new();
+		mgr.add_teachers(&mut teachers)?;
+		mgr.add_classes(&mut classes)?;
+		mgr.add_student_groups(&mut student_groups)?;
+
+		self.make_request(&mut mgr).await?;
+		Ok(Directory::new(teachers.get()?, classes.get()?, student_groups.get()?))
+	}
+
+	/// Substitutions and cancellations of the whole school between `start` and `end` (both inclusive);
+	/// group them by class using `transformers::smartv2::SubstitutionPlan`.
+	pub async fn get_substitution_plan(&self, start: NaiveDate, end: NaiveDate) -> Result<SubstitutionsResult> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Substitutions::new(start, end);
+		mgr.add_substitutions(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_conversations(&self) -> Result<Vec<sm::messenger::response::Subscription>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Subscriptions::new();
+		mgr.add_subscriptions(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_messages(&self, subscription_id: usize, offset: usize, limit: usize) -> Result<sm::messenger::response::MessagePage> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Messages::new(subscription_id, offset, limit)?;
+		mgr.add_messages(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn send_message(&self, mut message: SendMessage) -> Result<sm::messenger::response::Message> {
+		let mut mgr = RequestManager::new();
+
+		mgr.add_send_message(&mut message)?;
+
+		self.make_request(&mut mgr).await?;
+		message.get()
+	}
+
+	pub async fn mark_conversation_read(&self, subscription_id: usize) -> Result<()> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = MarkAsRead::new(subscription_id);
+		mgr.add_mark_as_read(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	pub async fn get_recipients(&self) -> Result<Vec<sm::messenger::response::Recipient>> {
+		let mut mgr = RequestManager::new();
+
+		let mut params = Recipients::new();
+		mgr.add_recipients(&mut params)?;
+
+		self.make_request(&mut mgr).await?;
+		params.get()
+	}
+
+	/// Starts downloading `attachment`; the body is streamed from the returned `SmAttachment`.
+	pub async fn download_attachment(&self, attachment: &sm::letters::response::Attachment) -> Result<SmAttachment> {
+		let mut request = Request::builder()
+			.method(Method::GET)
+			.uri(format!("https://login.schulmanager-online.de/api/file/letters/attachment/{}", attachment.id))
+			.body(())?;
+		set_jwt(request.headers_mut(), &self.token)?;
+
+		let response = self.client.send_async(request).await?;
+		if !response.status().is_success() {
+			return Err(errors::SmError::NonvalidStatusCode { statuscode: response.status().as_u16() }.into())
+		}
+		let filename = response.headers().get(header::CONTENT_DISPOSITION)
+			.and_then(|value| value.to_str().ok())
+			.and_then(http_util::content_disposition_filename)
+			.unwrap_or_else(|| attachment.filename.clone());
+		let mime_type = response.headers().get(header::CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.map(String::from)
+			.or_else(|| attachment.mime_type.clone());
+		Ok(SmAttachment {
+			filename,
+			mime_type,
+			body: response.into_body()
+		})
+	}
+}
+
+/// Attachment download, `body` implements `AsyncRead` and streams the file contents.
+pub struct SmAttachment {
+	pub filename: String,
+	pub mime_type: Option<String>,
+	pub body: AsyncBody
 }
 
 pub struct SmTimetable {
@@ -168,7 +769,7 @@ impl SmTimetable {
 pub struct SmHours(HoursResult);
 
 use std::collections::BTreeMap;
-use chrono::NaiveTime;
+use chrono::{NaiveDate, NaiveTime};
 
 pub type SchoolHours = Vec<(NaiveTime, NaiveTime)>;
 pub type SchoolHoursMap = BTreeMap<usize, SchoolHours>;
@@ -186,7 +787,7 @@ impl SmHours {
     }
     pub fn is_success(&self) -> bool {
         let status = self.0.status;
-        status >= 200 && status < 300
+        (200..300).contains(&status)
     }
 
     pub fn parse(&self) -> Result<SchoolHoursMap> {
@@ -201,11 +802,20 @@ impl SmHours {
 }
 
 #[cfg(test)]
+#[allow(deprecated, clippy::redundant_static_lifetimes, clippy::useless_conversion)]
 mod tests {
     use super::*;
     use chrono::{Local, Datelike, IsoWeek};
     use futures_test as fut;
 
+    /* feeds the batched results of a fixture to `requests`, in the order they were added */
+    fn load_fixture(path: &str, requests: Vec<&mut dyn sm::ModRequest>) -> Result<(), Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let result: ResultBody = serde_json::from_reader(std::io::BufReader::new(file))?;
+        RequestManager { _state: requests }.get_results(result)?;
+        Ok(())
+    }
+
     #[test]
     fn timetable_parser_test() -> Result<(), Box<dyn std::error::Error>> {
         const PATH: &'static str = "src/test_table.json";
@@ -227,6 +837,579 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn timetable_events_grouped_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::smartv2::{TimetableElement, EventType};
+        const PATH: &'static str = "src/test_table.json";
+        let file = std::fs::File::open(PATH)?;
+        let reader = std::io::BufReader::new(file);
+        let timetable: SmTimetable = SmTimetable::from_reader(Box::new(reader))?;
+        let smart = timetable.to_smart_v2_daymap()?;
+        let day = &smart.map[&chrono::NaiveDate::from_ymd_opt(2020, 10, 2).unwrap()];
+        let events: Vec<_> = day.values().flatten().filter_map(|tte| match tte {
+            TimetableElement::Event(event) => Some(event),
+            _ => None
+        }).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].from_hour, events[0].until_hour), (1, 2));
+        assert_eq!(events[0].absence_id, Some(24707));
+        assert_eq!(events[0].event_type, EventType::ClassAbsence);
+        assert!(!events[0].all_day);
+        assert!(matches!(day[&2][..], [TimetableElement::Cancelled(_)]));
+
+        let file = std::fs::File::open("src/test_hours.json")?;
+        let mut hours = SmHours::from_reader(Box::new(std::io::BufReader::new(file)))?.parse()?;
+        let mut smart = smart;
+        smart.mark_all_day(&hours);
+        let is_all_day = |smart: &transformers::smartv2::DayMap| smart.map[&chrono::NaiveDate::from_ymd_opt(2020, 10, 2).unwrap()].values().flatten().any(|tte| matches!(tte, TimetableElement::Event(event) if event.all_day));
+        assert!(!is_all_day(&smart));
+        hours.retain(|hour, _| *hour <= 2);
+        smart.mark_all_day(&hours);
+        assert!(is_all_day(&smart));
+        Ok(())
+    }
+
+    #[test]
+    fn timetable_ids_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::smartv2::{TimetableElement, LessonId, SubstitutionId, RoomId, TeacherId};
+        const PATH: &str = "src/test_table.json";
+        let file = std::fs::File::open(PATH)?;
+        let reader = std::io::BufReader::new(file);
+        let timetable: SmTimetable = SmTimetable::from_reader(Box::new(reader))?;
+        let smart = timetable.to_smart_v2_daymap()?;
+        let day = &smart.map[&chrono::NaiveDate::from_ymd_opt(2020, 9, 30).unwrap()];
+        match &day[&6][..] {
+            [TimetableElement::Substitution(actual, original)] => {
+                assert_eq!(actual.substitution_id, Some(SubstitutionId(22013)));
+                assert_eq!(actual.room_id, RoomId(14025));
+                assert_eq!(actual.teachers[0].id, TeacherId(11174));
+                assert_eq!(original.lesson_id, Some(LessonId(25628)));
+                assert_eq!(original.substitution_id, None);
+            },
+            other => panic!("expected a substitution, got {:?}", other)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn timetable_render_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::render::{Renderer, Format, Language};
+        let file = std::fs::File::open("src/test_table.json")?;
+        let timetable: SmTimetable = SmTimetable::from_reader(Box::new(std::io::BufReader::new(file)))?;
+        let file = std::fs::File::open("src/test_hours.json")?;
+        let hours = SmHours::from_reader(Box::new(std::io::BufReader::new(file)))?.parse()?;
+        let smart = timetable.to_smart_v2_daymap()?;
+
+        let markdown = Renderer::new(Format::Markdown, Language::German).with_hours(&hours).render_daymap(&smart);
+        assert!(markdown.contains("### Mittwoch, 30.09.2020"));
+        assert!(markdown.contains("T5 → T6"));
+        assert!(markdown.contains("~~Lesson 8~~"));
+        assert!(markdown.contains("| 1.–2. |"));
+        assert!(markdown.contains("09:35–10:20"));
+
+        let mut smart = smart;
+        if let Some(transformers::smartv2::TimetableElement::Lesson(lesson)) = smart.map.get_mut(&chrono::NaiveDate::from_ymd_opt(2020, 9, 28).unwrap()).unwrap().get_mut(&3).and_then(|elements| elements.first_mut()) {
+            lesson.comment = Some(String::from("*Raum_2* | [Plan](x)\nbitte `pünktlich`"));
+        }
+        let markdown = Renderer::new(Format::Markdown, Language::German).render_daymap(&smart);
+        assert!(markdown.contains("\\*Raum\\_2\\* \\| \\[Plan\\](x)<br>bitte \\`pünktlich\\`"));
+
+        let html = Renderer::new(Format::Html, Language::English).render_weekdays(&transformers::smartv2::Weekdays::from_daymap(smart));
+        assert!(html.contains("<h3>Friday</h3>"));
+        assert!(html.contains("<del>Lesson 8</del>"));
+        assert!(html.contains("Event (Room 1)"));
+        Ok(())
+    }
+
+    #[test]
+    fn timetable_csv_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::{csv::CsvExporter, smartv2::TimetableElement};
+        let file = std::fs::File::open("src/test_table.json")?;
+        let timetable: SmTimetable = SmTimetable::from_reader(Box::new(std::io::BufReader::new(file)))?;
+        let file = std::fs::File::open("src/test_hours.json")?;
+        let hours = SmHours::from_reader(Box::new(std::io::BufReader::new(file)))?.parse()?;
+        let mut smart = timetable.to_smart_v2_daymap()?;
+
+        let csv = CsvExporter::new().with_hours(&hours).export_daymap(&smart);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("\u{feff}date,weekday,hour,start,end,status,subject,room,teachers,classes,groups,comment,original_subject,original_room,original_teachers"));
+        assert!(lines.any(|line| line == "2020-09-30,Wednesday,6,12:05,12:50,substitution,Lesson 5,Room 5,T6,C1,,Substiution Comment,Lesson 5,Room 5,T5"));
+        assert!(csv.contains("2020-10-02,Friday,1-2,"));
+
+        let day = smart.map.get_mut(&chrono::NaiveDate::from_ymd_opt(2020, 9, 28).unwrap()).unwrap();
+        if let Some(TimetableElement::Lesson(lesson)) = day.get_mut(&3).and_then(|elements| elements.first_mut()) {
+            lesson.room = String::from("Raum 1, Gebäude \"Süd\"");
+        }
+        let csv = CsvExporter::new().with_bom(false).export_daymap(&smart);
+        assert!(csv.contains(",\"Raum 1, Gebäude \"\"Süd\"\"\","));
+        let csv = CsvExporter::new().with_bom(false).with_delimiter(';').export_daymap(&smart);
+        assert!(csv.contains(";\"Raum 1, Gebäude \"\"Süd\"\"\";"));
+
+        if let Some(TimetableElement::Lesson(lesson)) = smart.map.get_mut(&chrono::NaiveDate::from_ymd_opt(2020, 9, 28).unwrap()).unwrap().get_mut(&3).and_then(|elements| elements.first_mut()) {
+            lesson.room = String::from("=HYPERLINK(\"http://example.com\")");
+            lesson.comment = Some(String::from("@SUM(1+1)"));
+        }
+        let csv = CsvExporter::new().with_bom(false).export_daymap(&smart);
+        assert!(csv.contains(",\"'=HYPERLINK(\"\"http://example.com\"\")\","));
+        assert!(csv.contains(",'@SUM(1+1),"));
+        let csv = CsvExporter::new().with_bom(false).with_formula_escape(false).export_daymap(&smart);
+        assert!(csv.contains(",@SUM(1+1),"));
+        Ok(())
+    }
+
+    #[test]
+    fn letters_parser_test() -> Result<(), Box<dyn std::error::Error>> {
+        let mut letters = Letters::new();
+        let mut letter = Letter::new(0);
+        load_fixture("src/test_letters.json", vec![&mut letters, &mut letter])?;
+
+        let letters = letters.get()?;
+        assert_eq!(letters.len(), 2);
+        assert!(letters[0].is_read(4242) && !letters[0].is_confirmed(4242));
+        assert!(letters[1].is_confirmed(4242));
+        assert!(!letters[1].is_read(1));
+        let letter = letter.get()?;
+        assert!(letter.requires_confirmation);
+        assert_eq!(letter.attachments[0].filename, "Einverständniserklärung.pdf");
+        Ok(())
+    }
+
+    #[test]
+    fn letter_form_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::letters::response::{FormFieldAnswer, FormAnswer};
+        let mut letters = Letters::new();
+        let mut letter = Letter::new(0);
+        load_fixture("src/test_letters.json", vec![&mut letters, &mut letter])?;
+        let letter = letter.get()?;
+        let form = letter.form.as_ref().unwrap();
+
+        let consent = FormFieldAnswer { field_id: 1, value: FormAnswer::Checkbox(true) };
+        let lunch = FormFieldAnswer { field_id: 2, value: FormAnswer::SingleChoice(21) };
+        assert!(form.validate(&[consent.clone(), lunch.clone()]).is_ok());
+        assert!(form.validate(std::slice::from_ref(&lunch)).is_err());
+        assert!(form.validate(&[consent.clone(), FormFieldAnswer { field_id: 2, value: FormAnswer::SingleChoice(99) }]).is_err());
+        assert!(form.validate(&[consent.clone(), FormFieldAnswer { field_id: 3, value: FormAnswer::Checkbox(true) }]).is_err());
+
+        let submit = SubmitForm::new(letter.id, 4242, form, vec![consent, lunch])?;
+        let value = sm::ModRequest::get_value(&submit)?;
+        assert_eq!(value["answers"][1], serde_json::json!({"fieldId": 2, "type": "single-choice", "value": 21}));
+        Ok(())
+    }
+
+    #[test]
+    fn content_disposition_test() {
+        assert_eq!(http_util::content_disposition_filename("attachment; filename=\"brief.pdf\""), Some(String::from("brief.pdf")));
+        assert_eq!(http_util::content_disposition_filename("attachment; filename=\"Einverstaendnis.pdf\"; filename*=UTF-8''Einverst%C3%A4ndnis.pdf"), Some(String::from("Einverständnis.pdf")));
+        assert_eq!(http_util::content_disposition_filename("inline"), None);
+    }
+
+    #[test]
+    fn messenger_parser_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::messenger::response::RecipientType;
+        let mut subscriptions = Subscriptions::new();
+        assert!(matches!(Messages::new(812, 0, 0), Err(err) if matches!(err.downcast_ref(), Some(errors::SmError::InvalidPageLimit))));
+        let mut messages = Messages::new(812, 0, 2)?;
+        let mut recipients = Recipients::new();
+        load_fixture("src/test_messenger.json", vec![&mut subscriptions, &mut messages, &mut recipients])?;
+
+        let subscriptions = subscriptions.get()?;
+        assert_eq!(subscriptions.iter().map(|sub| sub.unread_count).sum::<usize>(), 3);
+        let page = messages.get()?;
+        assert!(page.has_more);
+        assert_eq!(page.next_offset, 2);
+        let recipients = recipients.get()?;
+        assert_eq!(recipients[2].r#type, RecipientType::Unknown);
+        assert!(sm::ModRequest::get_value(&SendMessage::new_thread(String::from("Frage"), String::from("Hallo"), &recipients[2..])).is_err());
+
+        let reply = SendMessage::new_thread(String::from("Frage"), String::from("Hallo"), &recipients[..1]);
+        assert_eq!(sm::ModRequest::get_endpoint_name(&reply), "send-message");
+        assert_eq!(sm::ModRequest::get_value(&reply)?["recipients"], serde_json::json!([{"id": 6397, "type": "teacher"}]));
+        Ok(())
+    }
+
+    #[test]
+    fn calendar_merge_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::smartv2::{TimetableElement, EventType};
+        let mut calendar = Calendar::new(NaiveDate::from_ymd_opt(2020, 9, 28).unwrap(), NaiveDate::from_ymd_opt(2020, 10, 4).unwrap());
+        load_fixture("src/test_calendar.json", vec![&mut calendar])?;
+        let calendar = calendar.get()?;
+        assert!(calendar[0].all_day && calendar[0].targets_class(5919));
+        assert_eq!(calendar[0].dates().len(), 2);
+        assert!(!calendar[1].targets_class(1));
+        use sm::calendar::response::parse_datetime;
+        use chrono::TimeZone;
+        let utc = parse_datetime("2020-10-01T07:30:00Z");
+        assert_eq!(utc, Some(chrono::Utc.with_ymd_and_hms(2020, 10, 1, 7, 30, 0).unwrap().with_timezone(&Local).naive_local()));
+        assert_eq!(utc, parse_datetime("2020-10-01T09:30:00+02:00"));
+
+        let file = std::fs::File::open("src/test_table.json")?;
+        let timetable: SmTimetable = SmTimetable::from_reader(Box::new(std::io::BufReader::new(file)))?;
+        let file = std::fs::File::open("src/test_hours.json")?;
+        let hours = SmHours::from_reader(Box::new(std::io::BufReader::new(file)))?.parse()?;
+        let mut smart = timetable.to_smart_v2_daymap()?;
+        let mut other_class = smart.clone();
+        other_class.merge_calendar(&calendar, &hours, 1);
+        assert!(other_class.map.get(&NaiveDate::from_ymd_opt(2020, 9, 29).unwrap()).into_iter().flat_map(|day| day.values().flatten())
+            .all(|tte| !matches!(tte, TimetableElement::Event(event) if event.event_type == EventType::Calendar)));
+        smart.merge_calendar(&calendar, &hours, 5919);
+
+        let calendar_events = |date| smart.map[&date].values().flatten().filter_map(|tte| match tte {
+            TimetableElement::Event(event) if event.event_type == EventType::Calendar => Some(event.clone()),
+            _ => None
+        }).collect::<Vec<_>>();
+        let tuesday = calendar_events(NaiveDate::from_ymd_opt(2020, 9, 29).unwrap());
+        assert_eq!(tuesday.len(), 1);
+        assert!(tuesday[0].all_day);
+        let thursday = calendar_events(NaiveDate::from_ymd_opt(2020, 10, 1).unwrap());
+        assert_eq!((thursday[0].from_hour, thursday[0].until_hour), (3, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn exams_parser_test() -> Result<(), Box<dyn std::error::Error>> {
+        let mut exams = Exams::new(0, 0, NaiveDate::from_ymd_opt(2020, 10, 1).unwrap(), NaiveDate::from_ymd_opt(2020, 12, 31).unwrap());
+        load_fixture("src/test_exams.json", vec![&mut exams])?;
+        let exams = exams.get()?;
+        assert_eq!(exams[0].hours(), vec![3, 4]);
+        assert_eq!(exams[0].subject.abbreviation, "L1");
+        assert_eq!(exams[0].date(), NaiveDate::from_ymd_opt(2020, 10, 14));
+        assert_eq!(exams[1].r#type.name, "Stegreifaufgabe");
+        Ok(())
+    }
+
+    #[test]
+    fn homework_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::homework::response::{group_by_due_date, due_on};
+        use chrono::TimeZone;
+        let mut homework = Homework::new(0, 0, NaiveDate::from_ymd_opt(2020, 9, 28).unwrap(), NaiveDate::from_ymd_opt(2020, 10, 4).unwrap());
+        load_fixture("src/test_homework.json", vec![&mut homework])?;
+        let homework = homework.get()?;
+
+        let grouped = group_by_due_date(&homework);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[&NaiveDate::from_ymd_opt(2020, 10, 1).unwrap()].len(), 2);
+        assert_eq!(due_on(&homework, NaiveDate::from_ymd_opt(2020, 10, 5).unwrap())[0].subject.abbreviation, "L5");
+
+        let ical = transformers::ical::homework_to_vtodo(&homework, chrono::Utc.with_ymd_and_hms(2020, 9, 30, 12, 0, 0).unwrap());
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VTODO").count(), 3);
+        assert!(ical.contains("UID:homework-881@schulmanager-online.de\r\n"));
+        assert!(ical.contains("DUE;VALUE=DATE:20201001\r\n"));
+        assert!(ical.contains("SUMMARY:Lesson 1: S. 42 Nr. 1\\, 2 und 5\r\n"));
+        assert!(ical.lines().all(|line| line.len() <= 75));
+        Ok(())
+    }
+
+    #[test]
+    fn sick_note_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::{AbsenceReport, sick_notes::response::SickNoteStatus};
+        let date = |d| NaiveDate::from_ymd_opt(2020, 10, d).unwrap();
+        let report = |period| AbsenceReport { student_id: 4242, period, reason: String::from("Krankheit"), comment: None };
+        let today = date(5);
+
+        assert!(report(AbsencePeriod::Days { from: date(5), until: date(7) }).validate(today).is_ok());
+        assert!(matches!(report(AbsencePeriod::Days { from: date(2), until: date(7) }).validate(today), Err(errors::SmError::AbsenceInPast { .. })));
+        assert!(matches!(report(AbsencePeriod::Days { from: date(9), until: date(10) }).validate(today), Err(errors::SmError::AbsenceOnWeekend { .. })));
+        assert!(matches!(report(AbsencePeriod::Days { from: date(7), until: date(6) }).validate(today), Err(errors::SmError::InvalidAbsencePeriod)));
+        assert!(matches!(report(AbsencePeriod::Hours { date: date(6), from_hour: 4, until_hour: 2 }).validate(today), Err(errors::SmError::InvalidAbsencePeriod)));
+
+        let submit = SubmitSickNote::new(report(AbsencePeriod::Hours { date: date(6), from_hour: 1, until_hour: 2 }), today)?;
+        assert_eq!(sm::ModRequest::get_value(&submit)?, serde_json::json!({
+            "studentId": 4242, "startDate": "2020-10-06", "endDate": "2020-10-06",
+            "fromClassHour": 1, "untilClassHour": 2, "reason": "Krankheit", "comment": null
+        }));
+
+        let mut notes = SickNotes::new(4242);
+        load_fixture("src/test_sick_notes.json", vec![&mut notes])?;
+        let notes = notes.get()?;
+        assert_eq!(notes[0].status, SickNoteStatus::Accepted);
+        assert_eq!(notes[1].status, SickNoteStatus::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn absences_summary_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::absences::response::{Totals, ExcuseStatus};
+        let mut absences = Absences::new(4242);
+        load_fixture("src/test_absences.json", vec![&mut absences])?;
+        let absences = absences.get()?;
+
+        assert_eq!(absences.by_id(24707).map(|absence| absence.excuse_status), Some(ExcuseStatus::Excused));
+
+        let file = std::fs::File::open("src/test_table.json")?;
+        let timetable = SmTimetable::from_reader(Box::new(std::io::BufReader::new(file)))?.to_smart_v2_daymap()?;
+        let event = timetable.map.values().flat_map(|day| day.values().flatten()).find_map(|tte| match tte {
+            transformers::smartv2::TimetableElement::Event(event) => Some(event),
+            _ => None
+        }).ok_or("timetable fixture has no event")?;
+        assert_eq!(absences.for_event(event).map(|absence| absence.comment.as_deref()), Some(Some("Krankheit")));
+        let summary = absences.summary();
+        assert_eq!(summary.total, Totals { missed_lessons: 4, excused_lessons: 3, unexcused_lessons: 1, late_count: 1, late_minutes: 10 });
+        assert_eq!(summary.per_term["1. Halbjahr"].missed_lessons, 3);
+        assert_eq!(summary.per_term["2. Halbjahr"].late_count, 1);
+        assert_eq!(summary.per_subject["Lesson 1"], Totals { missed_lessons: 2, excused_lessons: 1, unexcused_lessons: 1, late_count: 0, late_minutes: 0 });
+        assert_eq!(summary.per_subject["Lesson 5"].late_minutes, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn grades_average_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::grades::response::{GradingSystem, points_to_grade};
+        let grades = GradingSystem::Grades { modifier: 0.25 };
+        assert_eq!(grades.parse("2+"), Some(1.75));
+        assert_eq!(grades.parse("3-"), Some(3.25));
+        assert_eq!(grades.parse("2-3"), Some(2.5));
+        assert_eq!(grades.parse("2,5"), Some(2.5));
+        assert_eq!(grades.parse("1+"), Some(1.0));
+        assert_eq!(grades.parse("7"), None);
+        assert_eq!(grades.parse("n.e."), None);
+        assert_eq!(GradingSystem::Points.parse("13"), Some(13.0));
+        assert_eq!(GradingSystem::Points.parse("16"), None);
+        assert_eq!(points_to_grade(11.0), 2.0);
+
+        let mut params = Grades::new(4242);
+        load_fixture("src/test_grades.json", vec![&mut params])?;
+        let subjects = params.get()?;
+
+        let math = &subjects[0];
+        // written: 2 (weight 2) and 3- (weight 2) => 2.625; oral: 1+ and 2 => 1.875
+        assert_eq!(math.written_average(grades), Some(2.625));
+        assert_eq!(math.oral_average(grades), Some(1.875));
+        assert_eq!(math.average(grades), Some((2.0 * 2.0 + 3.25 * 2.0 + 1.75 + 2.0) / 6.0));
+        assert_eq!(math.split_average(grades, 0.5), Some(2.25));
+        let physics = &subjects[1];
+        assert_eq!(physics.split_average(GradingSystem::Points, 2.0 / 3.0), Some(12.0 * 2.0 / 3.0 + 9.0 / 3.0));
+        Ok(())
+    }
+
+    #[test]
+    fn consultation_plan_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::consultation::response::plan_slots;
+        let mut events = ConsultationEvents::new();
+        let mut t1 = Slots::new(71, 6397);
+        let mut t5 = Slots::new(71, 16479);
+        let mut bookings = Bookings::new(71);
+        load_fixture("src/test_consultation.json", vec![&mut events, &mut t1, &mut t5, &mut bookings])?;
+        assert!(events.get()?[0].booking_open);
+        let slots: Vec<_> = t1.get()?.into_iter().chain(t5.get()?).collect();
+        let bookings = bookings.get()?;
+
+        // T5 only has 17:55 left, the sibling's booking blocks 17:20, so T1 has to take 17:40
+        let plan = plan_slots(4242, &[6397, 16479], &slots, &bookings, chrono::Duration::minutes(5)).unwrap();
+        assert_eq!(plan.iter().map(|slot| slot.id).collect::<Vec<_>>(), vec![902, 951]);
+        assert!(plan_slots(4242, &[6397, 16479], &slots, &bookings, chrono::Duration::minutes(15)).is_none());
+
+        // the sibling's booking with T1 at 17:00 is planned around but doesn't take T1 off the list
+        let mut bookings = bookings;
+        bookings.push(sm::consultation::response::Booking { id: 3302, student_id: 4243, slot: slots[0].clone() });
+        let plan = plan_slots(4242, &[6397, 16479], &slots, &bookings, chrono::Duration::minutes(5)).unwrap();
+        assert_eq!(plan.iter().map(|slot| slot.id).collect::<Vec<_>>(), vec![902, 951]);
+
+        // T1 is already booked for this student, so only T5 gets a slot
+        bookings.push(sm::consultation::response::Booking { id: 3303, student_id: 4242, slot: slots[1].clone() });
+        let plan = plan_slots(4242, &[6397, 16479], &slots, &bookings, chrono::Duration::minutes(5)).unwrap();
+        assert_eq!(plan.iter().map(|slot| slot.id).collect::<Vec<_>>(), vec![951]);
+        Ok(())
+    }
+
+    #[test]
+    fn counters_batch_test() -> Result<(), Box<dyn std::error::Error>> {
+        let file = std::fs::File::open("src/test_counters.json")?;
+        let result: ResultBody = serde_json::from_reader(std::io::BufReader::new(file))?;
+        let mut mgr = RequestManager::new();
+        let mut counters = Counters::new();
+        let mut letters = Letters::new();
+        mgr.add_counters(&mut counters)?;
+        mgr.add_letters(&mut letters)?;
+        assert_eq!(mgr.get_request()?.requests.len(), 2);
+        mgr.get_results(result)?;
+
+        let counters = counters.get()?;
+        assert_eq!((counters.letters, counters.messages, counters.notifications), (1, 3, 0));
+        assert_eq!(counters.other["surveys"], 2);
+        assert!(counters.other.contains_key("lastUpdate"));
+        // numeric, but not a counter
+        assert_eq!(counters.other["version"], 17);
+        assert_eq!(counters.total(), 6);
+        assert_eq!(letters.get()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn surveys_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::surveys::response::{QuestionAnswer, FormAnswer, FormFieldKind};
+        let mut surveys = Surveys::new();
+        let mut survey = Survey::new(42);
+        let mut submissions = Submissions::new();
+        load_fixture("src/test_surveys.json", vec![&mut surveys, &mut survey, &mut submissions])?;
+        assert!(!surveys.get()?[0].submitted);
+        let survey = survey.get()?;
+        assert_eq!(submissions.get()?[0].answers[0].value, FormAnswer::MultipleChoice(vec![2, 3]));
+
+        let course = QuestionAnswer { question_id: 1, value: FormAnswer::SingleChoice(11) };
+        let trips = QuestionAnswer { question_id: 2, value: FormAnswer::MultipleChoice(vec![21, 22]) };
+        assert!(survey.validate(&[course.clone(), trips.clone()]).is_ok());
+        assert!(survey.validate(std::slice::from_ref(&trips)).is_err());
+        assert!(survey.validate(&[course.clone(), QuestionAnswer { question_id: 2, value: FormAnswer::MultipleChoice(vec![21, 22, 23]) }]).is_err());
+        assert!(survey.validate(&[course.clone(), QuestionAnswer { question_id: 3, value: FormAnswer::Text("x".repeat(201)) }]).is_err());
+        // question 4 has a type this crate doesn't know, so it can't be answered
+        assert!(matches!(survey.questions[3].kind, FormFieldKind::Unknown));
+        assert!(matches!(survey.validate(&[course.clone(), QuestionAnswer { question_id: 4, value: FormAnswer::Text("x".into()) }]),
+            Err(errors::SmError::InvalidFormAnswer { field_id: 4 })));
+        assert!(matches!(survey.validate(&[course.clone(), QuestionAnswer { question_id: 1, value: FormAnswer::SingleChoice(12) }]),
+            Err(errors::SmError::InvalidFormAnswer { field_id: 1 })));
+
+        let submit = SubmitSurvey::new(&survey, 4242, vec![course, trips])?;
+        assert_eq!(sm::ModRequest::get_value(&submit)?["answers"][0], serde_json::json!({"questionId": 1, "type": "single-choice", "value": 11}));
+        Ok(())
+    }
+
+    #[test]
+    fn learning_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::learning::response::{EntryKind, open_by_deadline};
+        let mut courses = Courses::new();
+        let mut entries = CourseEntries::new(31);
+        let mut assignments = Assignments::new();
+        load_fixture("src/test_learning.json", vec![&mut courses, &mut entries, &mut assignments])?;
+
+        assert_eq!(courses.get()?[0].teachers[0].abbreviation.as_deref(), Some("Mei"));
+        let entries = entries.get()?;
+        assert!(matches!(&entries[0].kind, EntryKind::Material { files, .. } if files.len() == 1));
+        assert!(matches!(&entries[1].kind, EntryKind::Assignment { assignment } if assignment.id == 77));
+        assert!(matches!(&entries[2].kind, EntryKind::Other));
+
+        let assignments = assignments.get()?;
+        let now = NaiveDate::from_ymd_opt(2021, 1, 20).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        assert!(assignments[0].is_overdue(now));
+        assert!(!assignments[1].is_overdue(now));
+        assert!(!assignments[2].is_overdue(now));
+        assert_eq!(open_by_deadline(&assignments).iter().map(|assignment| assignment.id).collect::<Vec<usize>>(), vec![76, 78]);
+        Ok(())
+    }
+
+    #[test]
+    fn multipart_test() {
+        let body = http_util::multipart_file("XYZ", "file", "Referat \"final\".pdf", "application/pdf", b"%PDF");
+        assert_eq!(String::from_utf8(body).unwrap(), "--XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"Referat %22final%22.pdf\"\r\nContent-Type: application/pdf\r\n\r\n%PDF\r\n--XYZ--\r\n");
+        let boundary = http_util::multipart_boundary(b"data");
+        assert!(boundary.starts_with("----libschulmanager"));
+    }
+
+    #[test]
+    fn status_messages_test() -> Result<(), Box<dyn std::error::Error>> {
+        use sm::Severity;
+        let file = std::fs::File::open("src/test_status.json")?;
+        let result: ResultBody = serde_json::from_reader(std::io::BufReader::new(file))?;
+        let messages = result.system_status_messages.clone();
+        assert_eq!(messages.iter().map(|message| message.severity).collect::<Vec<Severity>>(), vec![Severity::Warning, Severity::Info, Severity::Unknown]);
+
+        let now = NaiveDate::from_ymd_opt(2021, 1, 22).unwrap().and_hms_opt(18, 0, 0).unwrap();
+        assert!(messages[0].is_active(now));
+        assert!(!messages[1].is_active(now));
+        assert!(messages[2].is_active(now));
+
+        let mut mgr = RequestManager::new();
+        let mut counters = Counters::new();
+        mgr.add_counters(&mut counters)?;
+        mgr.get_results(result)?;
+        assert_eq!(counters.get()?.letters, 1);
+
+        // messages in an unexpected shape are dropped, the results still come through
+        let file = std::fs::File::open("src/test_status_malformed.json")?;
+        let result: ResultBody = serde_json::from_reader(std::io::BufReader::new(file))?;
+        assert_eq!(result.system_status_messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![Some(5)]);
+        let mut counters = Counters::new();
+        RequestManager { _state: vec![&mut counters] }.get_results(result)?;
+        assert_eq!(counters.get()?.notifications, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_version_test() -> Result<(), Box<dyn std::error::Error>> {
+        let index = r#"<!doctype html><html lang="de"><head><link rel="stylesheet" href="styles.8d1e6f0a3b.css"></head>
+            <body><sm-root></sm-root><script src="runtime.5c1f2a9e0b7d.js" defer></script><script src="polyfills.0f6b3c2d1a9e.js" defer></script>
+            <script src="main.3a7c91e4f02b6d58.js" defer></script></body></html>"#;
+        assert_eq!(sm::parse_bundle_version(index).as_deref(), Some("3a7c91e4f02b6d58"));
+        assert_eq!(sm::parse_bundle_version(r#"<script src="main-es2015.9f2e4c1b7a.js" type="module"></script>"#).as_deref(), Some("9f2e4c1b7a"));
+        assert_eq!(sm::parse_bundle_version("<main>no scripts here</main>"), None);
+
+        assert!(sm::is_outdated_bundle(r#"{"outdatedBundleVersion":true}"#));
+        assert!(!sm::is_outdated_bundle(r#"{"error":"bundle of requests too large"}"#));
+        assert!(!sm::is_outdated_bundle(r#"{"results":[],"systemStatusMessages":[]}"#));
+
+        let mgr = RequestManager::new();
+        assert_eq!(serde_json::to_value(mgr.get_request_with_bundle_version("3a7c91e4f02b6d58")?)?["bundleVersion"], "3a7c91e4f02b6d58");
+        assert_eq!(serde_json::to_value(mgr.get_request()?)?["bundleVersion"], sm::FALLBACK_BUNDLE_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn institution_test() -> Result<(), Box<dyn std::error::Error>> {
+        let mut institution = Institution::new();
+        load_fixture("src/test_institution.json", vec![&mut institution])?;
+
+        let institution = institution.get()?;
+        assert_eq!(institution.name, "Gymnasium am Stadtpark");
+        assert_eq!(institution.address.as_ref().and_then(|address| address.city.as_deref()), Some("Musterstadt"));
+        assert!(institution.supports(&Letters::new()));
+        assert!(institution.has_module("schedules"));
+        assert!(!institution.supports(&Courses::new()));
+        Ok(())
+    }
+
+    #[test]
+    fn directory_test() -> Result<(), Box<dyn std::error::Error>> {
+        let mut teachers = Teachers::new();
+        let mut classes = Classes::new();
+        let mut student_groups = StudentGroups::new();
+        let mut timetable = Timetable::new(0, 0, 1, None);
+        load_fixture("src/test_directory.json", vec![&mut teachers, &mut classes, &mut student_groups, &mut timetable])?;
+
+        let directory = Directory::new(teachers.get()?, classes.get()?, student_groups.get()?);
+        let meier = &directory.teachers[&55];
+        assert_eq!(meier.full_name().as_deref(), Some("Anna Meier"));
+        assert_eq!(meier.subjects[0].abbreviation, "Ph");
+        assert!(meier.messenger_enabled);
+
+        let timetable = SmTimetable { interna_timetable: timetable.get()? }.with_directory(&directory);
+        let daymap = timetable.to_smart_v2_daymap()?;
+        let day = &daymap.map[&NaiveDate::from_ymd_opt(2021, 1, 18).unwrap()];
+        match &day[&1][0] {
+            transformers::smartv2::TimetableElement::Lesson(lesson) => {
+                assert_eq!(lesson.teachers[0].abbreviation, "Mei");
+                assert_eq!(lesson.teachers[0].lastname.as_deref(), Some("Meier"));
+                assert_eq!(lesson.classes, vec!["10a"]);
+                assert_eq!(lesson.student_groups, vec!["10a Physik"]);
+            },
+            element => panic!("unexpected element {:?}", element)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn substitution_plan_test() -> Result<(), Box<dyn std::error::Error>> {
+        use transformers::smartv2::{SubstitutionPlan, TimetableElement};
+        let today = NaiveDate::from_ymd_opt(2021, 1, 18).unwrap();
+        let mut substitutions = Substitutions::new(today, today.succ_opt().unwrap());
+        load_fixture("src/test_substitutions.json", vec![&mut substitutions])?;
+
+        let plan = SubstitutionPlan::from_interna(substitutions.get()?)?;
+        let today = plan.day(today).unwrap();
+        /* the regular lesson of 5b is left out, the shared cancellation is listed for both classes */
+        assert_eq!(today.keys().map(String::as_str).collect::<Vec<&str>>(), vec!["10a", "10b", "7c"]);
+        assert!(matches!(&today["10a"][&3][0], TimetableElement::Cancelled(lesson) if lesson.subject.abbreviation == "Sp"));
+        assert!(matches!(&today["10b"][&3][0], TimetableElement::Cancelled(_)));
+        match &today["7c"][&2][0] {
+            TimetableElement::Substitution(actual, original) => {
+                assert_eq!(actual.teachers[0].abbreviation, "Sch");
+                assert_eq!(original.teachers[0].abbreviation, "Mei");
+            },
+            element => panic!("unexpected element {:?}", element)
+        }
+        assert_eq!(plan.day(NaiveDate::from_ymd_opt(2021, 1, 19).unwrap()).unwrap().len(), 1);
+        Ok(())
+    }
+
     #[test]
     fn hours_parser_test() -> Result<(), Box<dyn std::error::Error>> {
         const PATH: &'static str = "src/test_hours.json";
@@ -238,6 +1421,80 @@ mod tests {
         Ok(())
     }
 
+	#[cfg(feature = "microsoft")]
+    #[test]
+    fn o365_parse_page_test() -> Result<(), Box<dyn std::error::Error>> {
+        let config = o365::O365Auth::parse_page(&std::fs::read_to_string("src/test_o365_login.html")?)?;
+        assert_eq!(config.sessionId, "5b1e0a97-6d5e-4c57-b0b5-5a4b26d30f00");
+        assert_eq!(config.canary, "fBQ6aE4JmzF8yMF2x+i2TQyVmHbmmlIbvxCZ1ShNexs=:1");
+
+        let config = o365::O365Auth::parse_page(&std::fs::read_to_string("src/test_o365_login_minified.html")?)?;
+        assert_eq!(config.sCtx, "rQQIARAA42Kw0skoKSkottLXLy7M0UvNyVDLyyx\"quoted\"-{brace}");
+        assert_eq!(config.sFT, "AQABAAEAAAD--DLA3VO7QrddgJg7WevrXYZ");
+
+        let err = o365::O365Auth::parse_page(&std::fs::read_to_string("src/test_o365_error.html")?).unwrap_err();
+        assert!(matches!(err.downcast_ref::<errors::SmError>(), Some(errors::SmError::UnknownMS)));
+        let err = o365::O365Auth::parse_page("<script>var $Config={\"sCtx\":1};</script>").unwrap_err();
+        assert!(matches!(err.downcast_ref::<errors::SmError>(), Some(errors::SmError::UnknownMS)));
+        assert!(o365::O365Auth::parse_page("").is_err());
+
+        assert_eq!(o365::extract_config("$Config = { \"a\": \"}\" } ;"), Some("{ \"a\": \"}\" }"));
+        assert_eq!(o365::extract_config("x.$Config||{}"), None);
+        assert_eq!(o365::extract_config("$Config={\"unterminated\""), None);
+        Ok(())
+    }
+
+	#[cfg(feature = "microsoft")]
+    #[test]
+    fn o365_interstitial_test() -> Result<(), Box<dyn std::error::Error>> {
+        use o365::{PageState, MfaChallenge, HiddenForm, parse_config, hidden_form};
+        let classify = |page: &str| -> Result<PageState, Box<dyn std::error::Error>> { Ok(PageState::classify(&parse_config(page)?)) };
+
+        assert_eq!(classify(&std::fs::read_to_string("src/test_o365_kmsi.html")?)?, PageState::Kmsi);
+        assert_eq!(classify(&std::fs::read_to_string("src/test_o365_mfa.html")?)?, PageState::Mfa(Some(MfaChallenge {
+            method: String::from("PhoneAppOTP"),
+            display: String::from("+XX XXXXXXXX12")
+        })));
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedTFA"};</script>"#)?, PageState::Mfa(None));
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedTFA","arrUserProofs":[{"authMethodId":"PhoneAppNotification","isDefault":true}]};</script>"#)?, PageState::Mfa(None));
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedTFA","arrUserProofs":[{"authMethodId":"OneWaySMS","display":"+XX XXXXXXXX34"},{"authMethodId":"PhoneAppOTP","isDefault":true}]};</script>"#)?, PageState::Mfa(Some(MfaChallenge {
+            method: String::from("PhoneAppOTP"),
+            display: String::new()
+        })));
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConsentV2","sCtx":"x"};</script>"#)?, PageState::ConsentRequired);
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedChangePassword"};</script>"#)?, PageState::PasswordExpired);
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedSignIn","sErrorCode":"50126"};</script>"#)?, PageState::InvalidCredentials);
+        assert_eq!(classify(r#"<script>$Config={"pgid":"ConvergedProofUpRedirect"};</script>"#)?, PageState::Unknown(Some(String::from("ConvergedProofUpRedirect"))));
+
+        assert_eq!(hidden_form(&std::fs::read_to_string("src/test_o365_hiddenform.html")?), Some(HiddenForm {
+            action: String::from("https://login.schulmanager-online.de/oidc/callback"),
+            fields: vec![
+                (String::from("code"), String::from("0.AQwAabc&def")),
+                (String::from("state"), String::from("s7Yt2")),
+                (String::from("session_state"), String::from("5b1e0a97"))
+            ]
+        }));
+        assert_eq!(hidden_form(&std::fs::read_to_string("src/test_o365_kmsi.html")?), None);
+        Ok(())
+    }
+
+	#[cfg(feature = "microsoft")]
+    #[test]
+    fn o365_sso_discovery_test() {
+        let page = r#"<div class="login-options"><a class="btn" href="https://login.schulmanager-online.de/oidc/1207">Mit Office 365 anmelden</a></div>"#;
+        assert_eq!(o365::parse_provider_id(page).as_deref(), Some("1207"));
+        assert_eq!(o365::parse_provider_id(r#"<a href="/oidc/">kaputt</a><a href="/oidc/88?school=x">ok</a>"#).as_deref(), Some("88"));
+        assert_eq!(o365::parse_provider_id("<p>no sso</p>"), None);
+
+        assert_eq!(o365::parse_tenant("https://login.microsoftonline.com/3f1c6a2e-5b7d-4e0a-9c8b-2d4e6f8a0b1c/oauth2/v2.0/authorize?client_id=abc").as_deref(), Some("3f1c6a2e-5b7d-4e0a-9c8b-2d4e6f8a0b1c"));
+        assert_eq!(o365::parse_tenant("https://login.microsoftonline.com/?x=1"), None);
+        assert_eq!(o365::parse_tenant("https://login.schulmanager-online.de/oidc/1207"), None);
+
+        let sso = SmOfficeSso::default();
+        assert_eq!((sso.provider_id.as_str(), sso.tenant.as_deref()), ("413", Some("82a6d564-b994-4598-aff6-e131f8cfb1ae")));
+        assert_eq!(SmOfficeSso::new(String::from("1207")).tenant, None);
+    }
+
 	#[cfg(feature = "microsoft")]
     #[fut::test]
     #[ignore]
@@ -2,18 +2,166 @@ mod response;
 use anyhow::Result;
 use html5ever::tendril::stream::TendrilSink;
 use kuchiki;
-use isahc::{prelude::*, HttpClient};
-
-macro_rules! skip_none {
-    ($res:expr) => {
-        match $res {
-            Some(val) => val,
-            None => {
-                //eprintln!("Nothing was defined");
-                continue;
+use isahc::{prelude::*, HttpClient, AsyncBody, Request, Response};
+use http::header;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use crate::errors::SmError;
+use response::{PageConfig, SasResponse};
+
+/* every interstitial page takes one round trip, give up instead of looping on unexpected pages */
+const MAX_INTERSTITIALS: usize = 8;
+/* second factors the user can answer with a one-time code */
+const CODE_METHODS: [&str; 2] = ["PhoneAppOTP", "OneWaySMS"];
+
+/* finds `$Config = {...}` in a script and returns the object literal, regardless of formatting */
+pub(crate) fn extract_config(script: &str) -> Option<&str> {
+    script.match_indices("$Config").find_map(|(idx, _)| {
+        let rest = script[idx + "$Config".len()..].trim_start();
+        let rest = rest.strip_prefix('=')?.trim_start();
+        if !rest.starts_with('{') {
+            return None;
+        }
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+        for (pos, c) in rest.char_indices() {
+            match (in_string, escaped, c) {
+                (true, true, _) => escaped = false,
+                (true, false, '\\') => escaped = true,
+                (true, false, '"') => in_string = false,
+                (true, false, _) => (),
+                (false, _, '"') => in_string = true,
+                (false, _, '{') => depth += 1,
+                (false, _, '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&rest[..=pos]);
+                    }
+                },
+                _ => ()
             }
         }
-    };
+        None
+    })
+}
+
+pub(crate) fn parse_config<T: DeserializeOwned>(data: &str) -> Result<T> {
+    let dom = kuchiki::parse_html()
+        .from_utf8()
+        .read_from(&mut data.as_bytes())?;
+    let scripts = dom.select("script")
+        .map_err(|_| anyhow::Error::new(SmError::UnknownMS).context("failed selecting scripts of the login page"))?;
+    let config_serialized = scripts
+        .filter_map(|script| extract_config(&script.as_node().text_contents()).map(String::from))
+        .next()
+        .ok_or_else(|| anyhow::Error::new(SmError::UnknownMS).context("login page does not contain $Config"))?;
+    serde_json::from_str(&config_serialized)
+        .map_err(|err| anyhow::Error::new(SmError::UnknownMS).context(format!("failed parsing $Config of the login page: {}", err)))
+}
+
+/* the form Microsoft auto-submits (response_mode=form_post) to hand the login over to the application */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HiddenForm {
+    pub action: String,
+    pub fields: Vec<(String, String)>
+}
+
+pub(crate) fn hidden_form(page: &str) -> Option<HiddenForm> {
+    let dom = kuchiki::parse_html()
+        .from_utf8()
+        .read_from(&mut page.as_bytes()).ok()?;
+    let form = dom.select_first("form[name=hiddenform]").ok()?;
+    let action = form.attributes.borrow().get("action")?.to_owned();
+    let fields = form.as_node().select("input").ok()?
+        .filter_map(|input| {
+            let attributes = input.attributes.borrow();
+            Some((attributes.get("name")?.to_owned(), attributes.get("value").unwrap_or_default().to_owned()))
+        })
+        .collect();
+    Some(HiddenForm { action, fields })
+}
+
+fn absolute(url: &str) -> String {
+    if url.starts_with('/') {
+        format!("https://login.microsoftonline.com{}", url)
+    } else {
+        url.to_owned()
+    }
+}
+
+/* first `/oidc/<id>` link of a page, e.g. the "Login with Office 365" button */
+pub(crate) fn parse_provider_id(page: &str) -> Option<String> {
+    page.match_indices("/oidc/").find_map(|(idx, _)| {
+        let rest = &page[idx + "/oidc/".len()..];
+        let id_len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if id_len > 0 {
+            Some(rest[..id_len].to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/* the tenant is the first path segment of the Microsoft authorize url the provider redirects to */
+pub(crate) fn parse_tenant(url: &str) -> Option<String> {
+    let path = url.strip_prefix("https://login.microsoftonline.com/")?;
+    let tenant = path.split(['/', '?']).next()?;
+    if tenant.is_empty() {
+        None
+    } else {
+        Some(tenant.to_owned())
+    }
+}
+
+/// Second factor requested by Microsoft, handed to the async hook of `O365Auth::login_with_mfa`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MfaChallenge {
+    /// e.g. `PhoneAppOTP` or `OneWaySMS`
+    pub method: String,
+    /// masked phone number or device name the code was sent to
+    pub display: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PageState {
+    /// "Stay signed in?"
+    Kmsi,
+    /// `None` if the account has no code-based second factor (e.g. only push notifications)
+    Mfa(Option<MfaChallenge>),
+    ConsentRequired,
+    PasswordExpired,
+    InvalidCredentials,
+    Unknown(Option<String>)
+}
+impl PageState {
+    pub(crate) fn classify(config: &PageConfig) -> Self {
+        let pgid = config.pgid.as_deref().unwrap_or_default();
+        match config.sErrorCode.as_deref() {
+            /* invalid password, unknown user */
+            Some("50126") | Some("50034") => return PageState::InvalidCredentials,
+            Some("50055") => return PageState::PasswordExpired,
+            Some("65001") => return PageState::ConsentRequired,
+            _ => ()
+        }
+        if pgid == "KmsiInterrupt" {
+            PageState::Kmsi
+        } else if pgid == "ConvergedTFA" || !config.arrUserProofs.is_empty() {
+            /* push notifications and calls don't produce a code to pass to EndAuth, leave those accounts to `MSMfaRequired` */
+            let mut proofs = config.arrUserProofs.iter().filter(|proof| CODE_METHODS.contains(&proof.authMethodId.as_str()));
+            let proof = proofs.clone().find(|proof| proof.isDefault).or_else(|| proofs.next());
+            PageState::Mfa(proof.map(|proof| MfaChallenge {
+                method: proof.authMethodId.clone(),
+                display: proof.display.clone()
+            }))
+        } else if pgid.starts_with("Consent") {
+            PageState::ConsentRequired
+        } else if pgid == "ConvergedChangePassword" {
+            PageState::PasswordExpired
+        } else if pgid == "ConvergedSignIn" && config.sErrorCode.is_some() {
+            PageState::InvalidCredentials
+        } else {
+            PageState::Unknown(config.pgid.clone())
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -24,46 +172,133 @@ pub struct O365Auth {
 }
 impl O365Auth {
     pub async fn new(req_url: String, app_id: String) -> Result<Self> {
+        Self::init(&req_url, Some(app_id)).await
+    }
+    /// Like `new`, but takes the tenant from the Microsoft login page `req_url` redirects to.
+    pub async fn discover(req_url: String) -> Result<Self> {
+        Self::init(&req_url, None).await
+    }
+    async fn init(req_url: &str, app_id: Option<String>) -> Result<Self> {
         let client = HttpClient::builder()
             .cookies()
             .redirect_policy(isahc::config::RedirectPolicy::Follow)
             .build()?;
-        let mut initial = client.get_async(&req_url).await?;
+        let mut initial = client.get_async(req_url).await?;
+        let app_id = match app_id {
+            Some(app_id) => app_id,
+            None => initial.effective_uri()
+                .and_then(|uri| parse_tenant(&uri.to_string()))
+                .ok_or_else(|| anyhow::Error::new(SmError::NonvalidAppId).context(format!("{} did not redirect to a Microsoft tenant", req_url)))?
+        };
         Ok(O365Auth {
             req_client: client,
-            o365_app_id: app_id.to_string(),
-            data: O365Auth::parse_page(initial.text().await?).await?
+            o365_app_id: app_id,
+            data: O365Auth::parse_page(&initial.text().await?)?
         })
     }
-    async fn parse_page(data: String) -> Result<response::InitO365> {
-        let dom = kuchiki::parse_html()
-            .from_utf8()
-            .read_from(&mut data.as_bytes())?;
-        let mut config_serialized: String = String::from("");
-        for script in dom.select("script").unwrap() {
-            let as_node = script.as_node();
-            let text_node = skip_none!(as_node.first_child());
-            let text = text_node.as_text().unwrap().borrow();
-            if text[..20] == String::from("//<![CDATA[\n$Config=") {
-                config_serialized = String::from(&text[20..text.len()-7]);
-            }
+    pub(crate) fn parse_page(data: &str) -> Result<response::InitO365> {
+        parse_config(data)
+    }
+
+    async fn post_form<T: serde::Serialize + ?Sized>(&self, url: &str, params: &T) -> Result<Response<AsyncBody>> {
+        let request = Request::post(url)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(serde_urlencoded::to_string(params)?)?;
+        Ok(self.req_client.send_async(request).await?)
+    }
+
+    async fn post_json<T: DeserializeOwned + Unpin>(&self, url: &str, body: &serde_json::Value) -> Result<T> {
+        let request = Request::post(url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(body)?)?;
+        Ok(self.req_client.send_async(request).await?.json().await?)
+    }
+
+    async fn submit_kmsi(&self, config: &PageConfig) -> Result<Response<AsyncBody>> {
+        let params = [
+            ("LoginOptions", "1"), ("type", "28"),
+            ("ctx", config.sCtx.as_deref().unwrap_or_default()), ("hpgrequestid", config.sessionId.as_deref().unwrap_or_default()),
+            ("flowToken", config.sFT.as_deref().unwrap_or_default()), ("canary", config.canary.as_deref().unwrap_or_default())
+        ];
+        self.post_form(&absolute(config.urlPost.as_deref().unwrap_or("/kmsi")), &params).await
+    }
+
+    async fn submit_mfa(&self, config: &PageConfig, challenge: &MfaChallenge, email: &str, code: String) -> Result<Response<AsyncBody>> {
+        let begin: SasResponse = self.post_json(&absolute(config.urlBeginAuth.as_deref().unwrap_or("/common/SAS/BeginAuth")), &serde_json::json!({
+            "AuthMethodId": challenge.method,
+            "Method": "BeginAuth",
+            "ctx": config.sCtx,
+            "flowToken": config.sFT
+        })).await?;
+        if !begin.Success {
+            return Err(anyhow::Error::new(SmError::UnknownMS).context(format!("Microsoft refused to start {} verification", challenge.method)))
         }
-        let parsed: response::InitO365 = serde_json::from_str(&config_serialized)?;
-        Ok(parsed)
+        let end: SasResponse = self.post_json(&absolute(config.urlEndAuth.as_deref().unwrap_or("/common/SAS/EndAuth")), &serde_json::json!({
+            "AuthMethodId": challenge.method,
+            "Method": "EndAuth",
+            "SessionId": begin.SessionId,
+            "FlowToken": begin.FlowToken,
+            "Ctx": begin.Ctx,
+            "AdditionalAuthData": code,
+            "PollCount": 1
+        })).await?;
+        if !end.Success {
+            return Err(SmError::InvalidMSMfaCode.into())
+        }
+        let params = [
+            ("type", "19"), ("GeneralVerify", "false"),
+            ("request", end.Ctx.as_deref().unwrap_or_default()), ("mfaAuthMethod", &challenge.method),
+            ("canary", config.canary.as_deref().unwrap_or_default()), ("otc", &code), ("login", email),
+            ("flowToken", end.FlowToken.as_deref().unwrap_or_default()), ("hpgrequestid", config.sessionId.as_deref().unwrap_or_default())
+        ];
+        self.post_form(&absolute(config.urlPost.as_deref().unwrap_or("/common/SAS/ProcessAuth")), &params).await
     }
+
+    /// Logs in, confirming "Stay signed in?" prompts on the way.
+    ///
+    /// Accounts requiring a second factor fail with `SmError::MSMfaRequired`, see `login_with_mfa`.
     pub async fn login(&self, email: String, password: String) -> Result<()> {
+        self.login_with_mfa(email, password, |_| async { None }).await
+    }
+
+    /// Like `login`, but asks `mfa_code` for the one-time code if the account requires a second factor.
+    /// The returned future is awaited while the login waits, so it can prompt the user or poll another service.
+    /// Resolving to `None` aborts the login with `SmError::MSMfaRequired`, as do accounts offering neither
+    /// authenticator app codes nor SMS (e.g. push notifications only).
+    pub async fn login_with_mfa<F, Fut>(&self, email: String, password: String, mfa_code: F) -> Result<()>
+    where
+        F: Fn(MfaChallenge) -> Fut,
+        Fut: Future<Output = Option<String>>
+    {
         let login_params = [("login", &email), ("passwd", &password), ("canary", &self.data.canary), ("ctx", &self.data.sCtx), ("hpgrequestid", &self.data.sessionId), ("flowToken", &self.data.sFT)];
         let post_url: String = format!("https://login.microsoftonline.com/{}/login", self.o365_app_id);
 
-        let body = serde_urlencoded::to_string(&login_params)?;
-
-        let login_req = self.req_client.post_async(&post_url, body).await?;
+        let mut response = self.post_form(&post_url, &login_params).await?;
 
-        if login_req.headers().get("x-ms-request-id").is_some() {
-        	// still on M$ server, likely incorrect auth data
-        	Err(crate::errors::SmError::InvalidMSCredentials.into())
-        } else {
-        	Ok(())
+        for _ in 0..MAX_INTERSTITIALS {
+            if response.headers().get("x-ms-request-id").is_none() {
+                // left the M$ server, back at the application
+                return Ok(())
+            }
+            let page = response.text().await?;
+            if let Some(form) = hidden_form(&page) {
+                response = self.post_form(&form.action, &form.fields).await?;
+                continue;
+            }
+            let config: PageConfig = parse_config(&page)?;
+            response = match PageState::classify(&config) {
+                PageState::Kmsi => self.submit_kmsi(&config).await?,
+                PageState::Mfa(Some(challenge)) => {
+                    let code = mfa_code(challenge.clone()).await.ok_or(SmError::MSMfaRequired)?;
+                    self.submit_mfa(&config, &challenge, &email, code).await?
+                },
+                PageState::Mfa(None) => return Err(SmError::MSMfaRequired.into()),
+                PageState::ConsentRequired => return Err(SmError::MSConsentRequired.into()),
+                PageState::PasswordExpired => return Err(SmError::MSPasswordExpired.into()),
+                PageState::InvalidCredentials => return Err(SmError::InvalidMSCredentials.into()),
+                PageState::Unknown(pgid) => return Err(anyhow::Error::new(SmError::UnknownMS).context(format!("unexpected Microsoft page '{}'", pgid.unwrap_or_default())))
+            };
         }
+        Err(anyhow::Error::new(SmError::UnknownMS).context("too many Microsoft interstitial pages"))
     }
 }
@@ -12,3 +12,39 @@ pub struct InitO365 {
 }
 
 /* Credential Response */
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UserProof {
+    pub authMethodId: String,
+    /// masked phone number or device name
+    #[serde(default)]
+    pub display: String,
+    #[serde(default)]
+    pub isDefault: bool
+}
+
+/// `$Config` of the pages shown after submitting the credentials
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PageConfig {
+    pub pgid: Option<String>,
+    pub sErrorCode: Option<String>,
+    pub sCtx: Option<String>,
+    pub sFT: Option<String>,
+    pub canary: Option<String>,
+    pub sessionId: Option<String>,
+    pub urlPost: Option<String>,
+    pub urlBeginAuth: Option<String>,
+    pub urlEndAuth: Option<String>,
+    pub arrUserProofs: Vec<UserProof>
+}
+
+/* SAS (BeginAuth / EndAuth) Response */
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SasResponse {
+    pub Success: bool,
+    pub FlowToken: Option<String>,
+    pub Ctx: Option<String>,
+    pub SessionId: Option<String>
+}
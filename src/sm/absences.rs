@@ -0,0 +1,204 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AbsencesParams {
+        pub student_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Absences {
+        params: AbsencesParams,
+        result: Option<super::response::Result>
+    }
+    impl Absences {
+        pub fn new(student_id: usize) -> Self {
+            Self {
+                params: AbsencesParams {
+                    student_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Absences {
+        fn get_module_name(&self) -> &'static str {"absences"}
+        fn get_endpoint_name(&self) -> &'static str {"get-absences-for-student"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing absences")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_absences(&mut self, absences: &'l mut Absences) -> ERes<()> {
+            self._state.push(absences);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::{ClassHour, Subject};
+    use serde::{Serialize, Deserialize};
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum AbsenceKind {
+        Absence,
+        Lateness
+    }
+
+    #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ExcuseStatus {
+        Excused,
+        Unexcused,
+        /// no excuse was handed in yet
+        Open
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AffectedLesson {
+        pub date: String,
+        pub class_hour: ClassHour,
+        pub subject: Option<Subject>
+    }
+    impl AffectedLesson {
+        pub fn date(&self) -> Option<NaiveDate> {
+            NaiveDate::parse_from_str(&self.date, "%F").ok()
+        }
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Absence {
+        /// matches `Event::absence_id` of the timetable
+        pub id: usize,
+        pub r#type: AbsenceKind,
+        pub start_date: String,
+        pub end_date: String,
+        pub excuse_status: ExcuseStatus,
+        pub minutes_late: Option<u32>,
+        pub comment: Option<String>,
+        #[serde(default)]
+        pub lessons: Vec<AffectedLesson>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Term {
+        pub id: usize,
+        pub name: String,
+        pub start: String,
+        pub end: String
+    }
+    impl Term {
+        pub fn contains(&self, date: NaiveDate) -> bool {
+            match (NaiveDate::parse_from_str(&self.start, "%F"), NaiveDate::parse_from_str(&self.end, "%F")) {
+                (Ok(start), Ok(end)) => start <= date && date <= end,
+                _ => false
+            }
+        }
+    }
+
+    #[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Totals {
+        pub missed_lessons: usize,
+        pub excused_lessons: usize,
+        pub unexcused_lessons: usize,
+        pub late_count: usize,
+        pub late_minutes: u32
+    }
+    impl Totals {
+        fn add(&mut self, absence: &Absence, lessons: usize) {
+            match absence.r#type {
+                AbsenceKind::Absence => {
+                    self.missed_lessons += lessons;
+                    match absence.excuse_status {
+                        ExcuseStatus::Excused => self.excused_lessons += lessons,
+                        ExcuseStatus::Unexcused => self.unexcused_lessons += lessons,
+                        ExcuseStatus::Open => ()
+                    }
+                },
+                AbsenceKind::Lateness => {
+                    self.late_count += 1;
+                    self.late_minutes += absence.minutes_late.unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    #[derive(Serialize, Debug, Clone, Default)]
+    pub struct Summary {
+        pub total: Totals,
+        /// keyed by `Term::name`
+        pub per_term: BTreeMap<String, Totals>,
+        /// keyed by `Subject::name`
+        pub per_subject: BTreeMap<String, Totals>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Result {
+        pub absences: Vec<Absence>,
+        #[serde(default)]
+        pub terms: Vec<Term>
+    }
+    impl Result {
+        pub fn by_id(&self, absence_id: usize) -> Option<&Absence> {
+            self.absences.iter().find(|absence| absence.id == absence_id)
+        }
+
+        /// The absence a timetable event refers to, see `Event::absence_id`
+        #[cfg(feature = "smartv2")]
+        pub fn for_event(&self, event: &crate::transformers::smartv2::Event) -> Option<&Absence> {
+            event.absence_id.and_then(|absence_id| self.by_id(absence_id))
+        }
+
+        fn term(&self, date: Option<NaiveDate>) -> Option<&Term> {
+            date.and_then(|date| self.terms.iter().find(|term| term.contains(date)))
+        }
+
+        pub fn summary(&self) -> Summary {
+            let mut summary = Summary::default();
+            for absence in self.absences.iter() {
+                match absence.r#type {
+                    AbsenceKind::Absence => {
+                        summary.total.add(absence, absence.lessons.len());
+                        for lesson in absence.lessons.iter() {
+                            if let Some(term) = self.term(lesson.date()) {
+                                summary.per_term.entry(term.name.clone()).or_default().add(absence, 1);
+                            }
+                            if let Some(subject) = &lesson.subject {
+                                summary.per_subject.entry(subject.name.clone()).or_default().add(absence, 1);
+                            }
+                        }
+                    },
+                    AbsenceKind::Lateness => {
+                        summary.total.add(absence, 0);
+                        if let Some(term) = self.term(NaiveDate::parse_from_str(&absence.start_date, "%F").ok()) {
+                            summary.per_term.entry(term.name.clone()).or_default().add(absence, 0);
+                        }
+                        if let Some(subject) = absence.lessons.first().and_then(|lesson| lesson.subject.as_ref()) {
+                            summary.per_subject.entry(subject.name.clone()).or_default().add(absence, 0);
+                        }
+                    }
+                }
+            }
+            summary
+        }
+    }
+}
@@ -0,0 +1,116 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+    use chrono::NaiveDate;
+
+    #[derive(Serialize, Debug)]
+    pub struct CalendarParams {
+        pub start: String,
+        pub end: String
+    }
+
+    #[derive(Debug)]
+    pub struct Calendar {
+        params: CalendarParams,
+        result: Option<super::response::Result>
+    }
+    impl Calendar {
+        /// Requests all calendar entries between `start` and `end` (both inclusive).
+        pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+            Self {
+                params: CalendarParams {
+                    start: start.format("%F").to_string(),
+                    end: end.format("%F").to_string()
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Calendar {
+        fn get_module_name(&self) -> &'static str {"calendar"}
+        fn get_endpoint_name(&self) -> &'static str {"get-events"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing calendar")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_calendar(&mut self, calendar: &'l mut Calendar) -> ERes<()> {
+            self._state.push(calendar);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::{Class, StudentGroup};
+    use serde::Deserialize;
+    use chrono::{NaiveDate, NaiveDateTime, DateTime, Local};
+
+    /* the calendar sends either plain dates (all-day entries) or local/RFC 3339 timestamps;
+       the latter are converted to local time so they line up with the school hours */
+    pub(crate) fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()
+            .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.with_timezone(&Local).naive_local()))
+            .or_else(|| NaiveDate::parse_from_str(value, "%F").ok().and_then(|date| date.and_hms_opt(0, 0, 0)))
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Category {
+        pub id: usize,
+        pub name: String,
+        pub color: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CalendarEvent {
+        pub id: usize,
+        pub summary: String,
+        pub description: Option<String>,
+        pub location: Option<String>,
+        pub start: String,
+        pub end: String,
+        #[serde(default)]
+        pub all_day: bool,
+        pub category: Option<Category>,
+        #[serde(default)]
+        pub classes: Vec<Class>,
+        #[serde(default)]
+        pub student_groups: Vec<StudentGroup>
+    }
+    impl CalendarEvent {
+        pub fn start(&self) -> Option<NaiveDateTime> {
+            parse_datetime(&self.start)
+        }
+        pub fn end(&self) -> Option<NaiveDateTime> {
+            parse_datetime(&self.end)
+        }
+        /// Every date this entry takes place on
+        pub fn dates(&self) -> Vec<NaiveDate> {
+            match (self.start(), self.end()) {
+                (Some(start), Some(end)) => start.date().iter_days().take_while(|date| *date <= end.date()).collect(),
+                (Some(start), None) => vec![start.date()],
+                _ => vec![]
+            }
+        }
+        /// Whether this entry is addressed to the class `class_id` (or to the whole school)
+        pub fn targets_class(&self, class_id: usize) -> bool {
+            (self.classes.is_empty() && self.student_groups.is_empty())
+                || self.classes.iter().any(|class| class.id == class_id)
+                || self.student_groups.iter().any(|group| group.class_id == Some(class_id))
+        }
+    }
+
+    pub type Result = Vec<CalendarEvent>;
+}
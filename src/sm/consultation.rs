@@ -0,0 +1,307 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct ConsultationEvents {
+        result: Option<super::response::Result>
+    }
+    impl ConsultationEvents {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for ConsultationEvents {
+        fn get_module_name(&self) -> &'static str {"consultation-days"}
+        fn get_endpoint_name(&self) -> &'static str {"get-events"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing consultation events")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SlotsParams {
+        pub event_id: usize,
+        pub teacher_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Slots {
+        params: SlotsParams,
+        result: Option<Vec<super::response::Slot>>
+    }
+    impl Slots {
+        pub fn new(event_id: usize, teacher_id: usize) -> Self {
+            Self {
+                params: SlotsParams {
+                    event_id,
+                    teacher_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Slot>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Slots {
+        fn get_module_name(&self) -> &'static str {"consultation-days"}
+        fn get_endpoint_name(&self) -> &'static str {"get-slots"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing consultation slots")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BookingsParams {
+        pub event_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Bookings {
+        params: BookingsParams,
+        result: Option<Vec<super::response::Booking>>
+    }
+    impl Bookings {
+        pub fn new(event_id: usize) -> Self {
+            Self {
+                params: BookingsParams {
+                    event_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Booking>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Bookings {
+        fn get_module_name(&self) -> &'static str {"consultation-days"}
+        fn get_endpoint_name(&self) -> &'static str {"get-bookings"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing consultation bookings")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BookSlotParams {
+        pub slot_id: usize,
+        pub student_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct BookSlot {
+        params: BookSlotParams,
+        result: Option<super::response::Booking>
+    }
+    impl BookSlot {
+        pub fn new(slot_id: usize, student_id: usize) -> Self {
+            Self {
+                params: BookSlotParams {
+                    slot_id,
+                    student_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Booking> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for BookSlot {
+        fn get_module_name(&self) -> &'static str {"consultation-days"}
+        fn get_endpoint_name(&self) -> &'static str {"book-slot"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing consultation booking")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CancelBookingParams {
+        pub booking_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct CancelBooking {
+        params: CancelBookingParams,
+        cancelled: bool
+    }
+    impl CancelBooking {
+        pub fn new(booking_id: usize) -> Self {
+            Self {
+                params: CancelBookingParams {
+                    booking_id
+                },
+                cancelled: false
+            }
+        }
+        pub fn get(&mut self) -> ERes<()> {
+            if self.cancelled {
+                Ok(())
+            } else {
+                Err(crate::errors::SmError::UninitializedData.into())
+            }
+        }
+    }
+    impl ModRequest for CancelBooking {
+        fn get_module_name(&self) -> &'static str {"consultation-days"}
+        fn get_endpoint_name(&self) -> &'static str {"cancel-booking"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, _value: Value) -> ERes<()> {
+            self.cancelled = true;
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_consultation_events(&mut self, events: &'l mut ConsultationEvents) -> ERes<()> {
+            self._state.push(events);
+            Ok(())
+        }
+        pub fn add_slots(&mut self, slots: &'l mut Slots) -> ERes<()> {
+            self._state.push(slots);
+            Ok(())
+        }
+        pub fn add_bookings(&mut self, bookings: &'l mut Bookings) -> ERes<()> {
+            self._state.push(bookings);
+            Ok(())
+        }
+        pub fn add_book_slot(&mut self, book: &'l mut BookSlot) -> ERes<()> {
+            self._state.push(book);
+            Ok(())
+        }
+        pub fn add_cancel_booking(&mut self, cancel: &'l mut CancelBooking) -> ERes<()> {
+            self._state.push(cancel);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::Teacher;
+    use crate::sm::calendar::response::parse_datetime;
+    use serde::Deserialize;
+    use chrono::{Duration, NaiveDateTime};
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConsultationEvent {
+        pub id: usize,
+        pub name: String,
+        pub date: String,
+        #[serde(default)]
+        pub booking_open: bool,
+        #[serde(default)]
+        pub teachers: Vec<Teacher>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Slot {
+        pub id: usize,
+        pub teacher_id: usize,
+        pub start: String,
+        pub end: String,
+        #[serde(default)]
+        pub available: bool,
+        pub room: Option<String>
+    }
+    impl Slot {
+        pub fn start(&self) -> Option<NaiveDateTime> {
+            parse_datetime(&self.start)
+        }
+        pub fn end(&self) -> Option<NaiveDateTime> {
+            parse_datetime(&self.end)
+        }
+        /* whether both slots are at least `gap` apart */
+        fn is_apart(&self, other: &Slot, gap: Duration) -> bool {
+            match (self.start(), self.end(), other.start(), other.end()) {
+                (Some(start), Some(end), Some(other_start), Some(other_end)) => end + gap <= other_start || other_end + gap <= start,
+                _ => false
+            }
+        }
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Booking {
+        pub id: usize,
+        pub student_id: usize,
+        pub slot: Slot
+    }
+
+    fn plan_from(candidates: &[(usize, Vec<&Slot>)], picked: &mut Vec<Slot>, min_gap: Duration) -> bool {
+        let (_, slots) = match candidates.first() {
+            Some(candidate) => candidate,
+            None => return true
+        };
+        for slot in slots {
+            if picked.iter().all(|other| slot.is_apart(other, min_gap)) {
+                picked.push((*slot).clone());
+                if plan_from(&candidates[1..], picked, min_gap) {
+                    return true
+                }
+                picked.pop();
+            }
+        }
+        false
+    }
+
+    /// Picks one available slot for every teacher in `teachers` so that no two appointments of student
+    /// `student_id` overlap and there are at least `min_gap` between them. `bookings` that already exist
+    /// (e.g. for siblings) are kept and planned around; teachers the student is already booked
+    /// with get no further slot. Returns `None` if no such set of slots exists.
+    pub fn plan_slots(student_id: usize, teachers: &[usize], slots: &[Slot], bookings: &[Booking], min_gap: Duration) -> Option<Vec<Slot>> {
+        let booked = |teacher: &usize| bookings.iter()
+            .any(|booking| booking.student_id == student_id && booking.slot.teacher_id == *teacher);
+        let mut candidates: Vec<(usize, Vec<&Slot>)> = teachers.iter().filter(|teacher| !booked(teacher)).map(|teacher| {
+            let mut own: Vec<&Slot> = slots.iter().filter(|slot| slot.teacher_id == *teacher && slot.available).collect();
+            own.sort_by_key(|slot| slot.start());
+            (*teacher, own)
+        }).collect();
+        // most constrained teachers first, which keeps the search small
+        candidates.sort_by_key(|(_, own)| own.len());
+
+        let mut picked: Vec<Slot> = bookings.iter().map(|booking| booking.slot.clone()).collect();
+        let fixed = picked.len();
+        if !plan_from(&candidates, &mut picked, min_gap) {
+            return None
+        }
+        let mut planned = picked.split_off(fixed);
+        planned.sort_by_key(|slot| teachers.iter().position(|teacher| *teacher == slot.teacher_id));
+        Some(planned)
+    }
+
+    pub type Result = Vec<ConsultationEvent>;
+}
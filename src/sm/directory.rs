@@ -0,0 +1,193 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct Teachers {
+        result: Option<super::response::Result>
+    }
+    impl Teachers {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Teachers {
+        fn get_module_name(&self) -> &'static str {"schedules"}
+        fn get_endpoint_name(&self) -> &'static str {"get-teachers"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing teachers")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Classes {
+        result: Option<Vec<crate::sm::timetable::response::Class>>
+    }
+    impl Classes {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<crate::sm::timetable::response::Class>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Classes {
+        fn get_module_name(&self) -> &'static str {"schedules"}
+        fn get_endpoint_name(&self) -> &'static str {"get-classes"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing classes")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct StudentGroups {
+        result: Option<Vec<crate::sm::timetable::response::StudentGroup>>
+    }
+    impl StudentGroups {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<crate::sm::timetable::response::StudentGroup>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for StudentGroups {
+        fn get_module_name(&self) -> &'static str {"schedules"}
+        fn get_endpoint_name(&self) -> &'static str {"get-student-groups"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing student groups")?);
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_teachers(&mut self, teachers: &'l mut Teachers) -> ERes<()> {
+            self._state.push(teachers);
+            Ok(())
+        }
+        pub fn add_classes(&mut self, classes: &'l mut Classes) -> ERes<()> {
+            self._state.push(classes);
+            Ok(())
+        }
+        pub fn add_student_groups(&mut self, groups: &'l mut StudentGroups) -> ERes<()> {
+            self._state.push(groups);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::{Subject, Teacher, Class, StudentGroup, Datum};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DirectoryTeacher {
+        pub id: usize,
+        pub abbreviation: String,
+        pub firstname: Option<String>,
+        pub lastname: Option<String>,
+        #[serde(default)]
+        pub subjects: Vec<Subject>,
+        pub email: Option<String>,
+        /// the teacher can be contacted using the messenger
+        #[serde(default)]
+        pub messenger_enabled: bool
+    }
+    impl DirectoryTeacher {
+        pub fn full_name(&self) -> Option<String> {
+            match (&self.firstname, &self.lastname) {
+                (Some(firstname), Some(lastname)) => Some(format!("{} {}", firstname, lastname)),
+                (None, Some(name)) | (Some(name), None) => Some(name.clone()),
+                (None, None) => None
+            }
+        }
+    }
+
+    /// Teachers, classes and student groups of the school, keyed by id
+    #[derive(Debug, Clone, Default)]
+    pub struct Directory {
+        pub teachers: BTreeMap<usize, DirectoryTeacher>,
+        pub classes: BTreeMap<usize, Class>,
+        pub student_groups: BTreeMap<usize, StudentGroup>
+    }
+    impl Directory {
+        pub fn new(teachers: Vec<DirectoryTeacher>, classes: Vec<Class>, student_groups: Vec<StudentGroup>) -> Self {
+            Self {
+                teachers: teachers.into_iter().map(|teacher| (teacher.id, teacher)).collect(),
+                classes: classes.into_iter().map(|class| (class.id, class)).collect(),
+                student_groups: student_groups.into_iter().map(|group| (group.id, group)).collect()
+            }
+        }
+
+        /// Fills names missing in `teacher` from the directory; names already present are kept.
+        pub fn complete_teacher(&self, teacher: &mut Teacher) {
+            if let Some(known) = self.teachers.get(&teacher.id) {
+                if teacher.abbreviation.is_empty() {
+                    teacher.abbreviation = known.abbreviation.clone();
+                }
+                if teacher.firstname.is_none() {
+                    teacher.firstname = known.firstname.clone();
+                }
+                if teacher.lastname.is_none() {
+                    teacher.lastname = known.lastname.clone();
+                }
+            }
+        }
+
+        fn complete(&self, teachers: &mut [Teacher], classes: &mut [Class], student_groups: &mut [StudentGroup]) {
+            teachers.iter_mut().for_each(|teacher| self.complete_teacher(teacher));
+            for class in classes.iter_mut().filter(|class| class.name.is_empty()) {
+                if let Some(known) = self.classes.get(&class.id) {
+                    class.name = known.name.clone();
+                }
+            }
+            for group in student_groups.iter_mut().filter(|group| group.name.is_empty()) {
+                if let Some(known) = self.student_groups.get(&group.id) {
+                    group.name = known.name.clone();
+                }
+            }
+        }
+
+        /// Fills missing teacher, class and student group names of a raw timetable before it gets transformed.
+        pub fn complete_timetable(&self, timetable: &mut [Datum]) {
+            for datum in timetable {
+                if let Some(lesson) = datum.actual_lesson.as_mut() {
+                    self.complete(&mut lesson.teachers, &mut lesson.classes, &mut lesson.student_groups);
+                }
+                for lesson in datum.original_lessons.iter_mut().flatten() {
+                    self.complete(&mut lesson.teachers, &mut lesson.classes, &mut lesson.student_groups);
+                }
+                if let Some(event) = datum.event.as_mut() {
+                    self.complete(&mut event.teachers, &mut event.classes, &mut event.student_groups);
+                }
+            }
+        }
+    }
+
+    pub type Result = Vec<DirectoryTeacher>;
+}
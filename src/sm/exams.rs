@@ -0,0 +1,98 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use crate::sm::timetable::request::TimetableBodyParamsStudent;
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+    use chrono::NaiveDate;
+
+    #[derive(Serialize, Debug)]
+    pub struct ExamsParams {
+        pub student: TimetableBodyParamsStudent,
+        pub start: String,
+        pub end: String
+    }
+
+    #[derive(Debug)]
+    pub struct Exams {
+        params: ExamsParams,
+        result: Option<super::response::Result>
+    }
+    impl Exams {
+        pub fn new(id: usize, class_id: usize, start: NaiveDate, end: NaiveDate) -> Self {
+            Self {
+                params: ExamsParams {
+                    student: TimetableBodyParamsStudent {
+                        id,
+                        class_id
+                    },
+                    start: start.format("%F").to_string(),
+                    end: end.format("%F").to_string()
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Exams {
+        fn get_module_name(&self) -> &'static str {"schedules"}
+        fn get_endpoint_name(&self) -> &'static str {"get-exams"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing exams")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_exams(&mut self, exams: &'l mut Exams) -> ERes<()> {
+            self._state.push(exams);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::{ClassHour, Subject, Class};
+    use serde::Deserialize;
+    use chrono::NaiveDate;
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct ExamType {
+        pub id: usize,
+        pub name: String,
+        pub color: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Exam {
+        pub id: usize,
+        pub date: String,
+        #[serde(default)]
+        pub class_hours: Vec<ClassHour>,
+        pub subject: Subject,
+        pub r#type: ExamType,
+        pub comment: Option<String>,
+        #[serde(default)]
+        pub classes: Vec<Class>
+    }
+    impl Exam {
+        pub fn date(&self) -> Option<NaiveDate> {
+            NaiveDate::parse_from_str(&self.date, "%F").ok()
+        }
+        /// Class hour numbers the exam is written in, as used for the keys of the smart representations
+        pub fn hours(&self) -> Vec<usize> {
+            let mut hours: Vec<usize> = self.class_hours.iter().filter_map(|hour| hour.number.parse().ok()).collect();
+            hours.sort_unstable();
+            hours
+        }
+    }
+
+    pub type Result = Vec<Exam>;
+}
@@ -0,0 +1,85 @@
+/* fields and answers shared by letter reply forms and surveys */
+use crate::errors::SmError;
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FormOption {
+    pub id: usize,
+    pub label: String
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum FormFieldKind {
+    Checkbox,
+    #[serde(rename_all = "camelCase")]
+    SingleChoice {
+        /* surveys call them choices */
+        #[serde(alias = "choices")]
+        options: Vec<FormOption>
+    },
+    #[serde(rename_all = "camelCase")]
+    MultipleChoice {
+        #[serde(alias = "choices")]
+        options: Vec<FormOption>,
+        max_choices: Option<usize>
+    },
+    #[serde(rename_all = "camelCase")]
+    Text { max_length: Option<usize> },
+    /// a kind of field this crate doesn't know yet; it can't be answered through `FormAnswer`
+    #[serde(other)]
+    Unknown
+}
+impl FormFieldKind {
+    pub fn accepts(&self, answer: &FormAnswer) -> bool {
+        let known = |options: &[FormOption], id: &usize| options.iter().any(|option| option.id == *id);
+        match (self, answer) {
+            (FormFieldKind::Checkbox, FormAnswer::Checkbox(_)) => true,
+            (FormFieldKind::SingleChoice { options }, FormAnswer::SingleChoice(id)) => known(options, id),
+            (FormFieldKind::MultipleChoice { options, max_choices }, FormAnswer::MultipleChoice(ids)) =>
+                ids.iter().all(|id| known(options, id)) && max_choices.map(|max| ids.len() <= max).unwrap_or(true),
+            (FormFieldKind::Text { max_length }, FormAnswer::Text(text)) => max_length.map(|max| text.chars().count() <= max).unwrap_or(true),
+            _ => false
+        }
+    }
+}
+
+/// A form field (letters) or question (surveys)
+#[derive(Deserialize, Debug, Clone)]
+pub struct FormField {
+    pub id: usize,
+    #[serde(alias = "text")]
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: FormFieldKind
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "kebab-case")]
+pub enum FormAnswer {
+    Checkbox(bool),
+    SingleChoice(usize),
+    MultipleChoice(Vec<usize>),
+    Text(String)
+}
+
+/// Checks `answers` (pairs of field id and answer) against `fields`: every answer has to belong to a
+/// field of a matching, known kind, no field may be answered twice and every required field has to be answered.
+pub fn validate<'a, I>(fields: &[FormField], answers: I) -> Result<(), SmError>
+where
+    I: IntoIterator<Item = (usize, &'a FormAnswer)> + Clone
+{
+    let mut answered = std::collections::HashSet::new();
+    for (field_id, answer) in answers.clone() {
+        match fields.iter().find(|field| field.id == field_id) {
+            Some(field) if field.kind.accepts(answer) && answered.insert(field_id) => (),
+            _ => return Err(SmError::InvalidFormAnswer { field_id })
+        }
+    }
+    match fields.iter().find(|field| field.required && !answers.clone().into_iter().any(|(field_id, _)| field_id == field.id)) {
+        Some(field) => Err(SmError::InvalidFormAnswer { field_id: field.id }),
+        None => Ok(())
+    }
+}
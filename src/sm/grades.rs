@@ -0,0 +1,168 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GradesParams {
+        pub student_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Grades {
+        params: GradesParams,
+        result: Option<super::response::Result>
+    }
+    impl Grades {
+        pub fn new(student_id: usize) -> Self {
+            Self {
+                params: GradesParams {
+                    student_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Grades {
+        fn get_module_name(&self) -> &'static str {"grades"}
+        fn get_endpoint_name(&self) -> &'static str {"get-grades"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing grades")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_grades(&mut self, grades: &'l mut Grades) -> ERes<()> {
+            self._state.push(grades);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::Subject;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum GradingSystem {
+        /// grades 1 (best) to 6; `modifier` is what a "+" subtracts and a "-" adds, e.g. 0.25 or 0.3
+        Grades { modifier: f64 },
+        /// upper school points 15 (best) to 0
+        Points
+    }
+    impl GradingSystem {
+        /// Numeric value of a grade like "2", "2+", "3-", "2-3", "2,5" or "13".
+        /// Returns `None` for entries that are not grades (e.g. "n.e.") or out of range.
+        pub fn parse(&self, value: &str) -> Option<f64> {
+            let value = value.trim();
+            match self {
+                GradingSystem::Grades { modifier } => {
+                    let (grade, shift) = if let Some(grade) = value.strip_suffix('+') {
+                        (grade, -modifier)
+                    } else if let Some((grade, next)) = value.split_once('-').filter(|(_, next)| !next.is_empty()) {
+                        let (grade, next): (f64, f64) = (grade.parse().ok()?, next.parse().ok()?);
+                        return if next == grade + 1.0 && (1.0..=6.0).contains(&next) { Some(grade + 0.5) } else { None }
+                    } else if let Some(grade) = value.strip_suffix('-') {
+                        (grade, *modifier)
+                    } else {
+                        (value, 0.0)
+                    };
+                    let grade: f64 = grade.replace(',', ".").parse().ok()?;
+                    if !(1.0..=6.0).contains(&grade) {
+                        return None
+                    }
+                    Some((grade + shift).clamp(1.0, 6.0))
+                },
+                GradingSystem::Points => {
+                    let points: u8 = value.parse().ok()?;
+                    if points <= 15 { Some(points as f64) } else { None }
+                }
+            }
+        }
+    }
+
+    /// Converts upper school points to the equivalent grade, 15 points being 0.67 and 0 points 5.67
+    pub fn points_to_grade(points: f64) -> f64 {
+        (17.0 - points) / 3.0
+    }
+
+    #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum GradeCategory {
+        Written,
+        Oral,
+        #[serde(other)]
+        Other
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct GradeType {
+        pub id: usize,
+        pub name: String,
+        pub category: GradeCategory,
+        pub weight: Option<f64>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Grade {
+        pub id: usize,
+        pub value: String,
+        pub r#type: GradeType,
+        /// overrides the weight of `r#type` if set
+        pub weight: Option<f64>,
+        pub date: String,
+        pub comment: Option<String>
+    }
+    impl Grade {
+        pub fn weight(&self) -> f64 {
+            self.weight.or(self.r#type.weight).unwrap_or(1.0)
+        }
+    }
+
+    fn weighted_average<'g>(grades: impl Iterator<Item = &'g Grade>, system: GradingSystem) -> Option<f64> {
+        let (sum, weights) = grades
+            .filter_map(|grade| system.parse(&grade.value).map(|value| (value, grade.weight())))
+            .fold((0.0, 0.0), |(sum, weights), (value, weight)| (sum + value * weight, weights + weight));
+        if weights > 0.0 { Some(sum / weights) } else { None }
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct SubjectGrades {
+        pub subject: Subject,
+        pub grades: Vec<Grade>
+    }
+    impl SubjectGrades {
+        /// Weighted average over all grades, ignoring their category
+        pub fn average(&self, system: GradingSystem) -> Option<f64> {
+            weighted_average(self.grades.iter(), system)
+        }
+        /// Weighted average of the written grades
+        pub fn written_average(&self, system: GradingSystem) -> Option<f64> {
+            weighted_average(self.grades.iter().filter(|grade| grade.r#type.category == GradeCategory::Written), system)
+        }
+        /// Weighted average of the oral grades; grades of any other category count as oral
+        pub fn oral_average(&self, system: GradingSystem) -> Option<f64> {
+            weighted_average(self.grades.iter().filter(|grade| grade.r#type.category != GradeCategory::Written), system)
+        }
+        /// Combines the written and oral averages, written grades making up `written_share` (e.g. 0.5 or 2.0 / 3.0).
+        /// If one of both is missing, the other one is returned.
+        pub fn split_average(&self, system: GradingSystem, written_share: f64) -> Option<f64> {
+            match (self.written_average(system), self.oral_average(system)) {
+                (Some(written), Some(oral)) => Some(written * written_share + oral * (1.0 - written_share)),
+                (written, oral) => written.or(oral)
+            }
+        }
+    }
+
+    pub type Result = Vec<SubjectGrades>;
+}
@@ -0,0 +1,114 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use crate::sm::timetable::request::TimetableBodyParamsStudent;
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+    use chrono::NaiveDate;
+
+    #[derive(Serialize, Debug)]
+    pub struct HomeworkParams {
+        pub student: TimetableBodyParamsStudent,
+        pub start: String,
+        pub end: String
+    }
+
+    #[derive(Debug)]
+    pub struct Homework {
+        params: HomeworkParams,
+        result: Option<super::response::Result>
+    }
+    impl Homework {
+        /// Requests the homework given between `start` and `end` (both inclusive).
+        pub fn new(id: usize, class_id: usize, start: NaiveDate, end: NaiveDate) -> Self {
+            Self {
+                params: HomeworkParams {
+                    student: TimetableBodyParamsStudent {
+                        id,
+                        class_id
+                    },
+                    start: start.format("%F").to_string(),
+                    end: end.format("%F").to_string()
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Homework {
+        fn get_module_name(&self) -> &'static str {"classbook"}
+        fn get_endpoint_name(&self) -> &'static str {"get-homework"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing homework")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_homework(&mut self, homework: &'l mut Homework) -> ERes<()> {
+            self._state.push(homework);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::timetable::response::Subject;
+    use serde::Deserialize;
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Attachment {
+        pub id: usize,
+        pub filename: String,
+        pub mime_type: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Homework {
+        pub id: usize,
+        /// the assignment itself
+        pub homework: String,
+        pub subject: Subject,
+        /// date the homework was given on
+        pub date: String,
+        pub due_date: Option<String>,
+        #[serde(default)]
+        pub attachments: Vec<Attachment>
+    }
+    impl Homework {
+        pub fn date(&self) -> Option<NaiveDate> {
+            NaiveDate::parse_from_str(&self.date, "%F").ok()
+        }
+        pub fn due_date(&self) -> Option<NaiveDate> {
+            self.due_date.as_ref().and_then(|date| NaiveDate::parse_from_str(date, "%F").ok())
+        }
+    }
+
+    /// Groups `homework` by due date; entries without a due date are left out.
+    pub fn group_by_due_date(homework: &[Homework]) -> BTreeMap<NaiveDate, Vec<&Homework>> {
+        let mut map: BTreeMap<NaiveDate, Vec<&Homework>> = BTreeMap::new();
+        for entry in homework {
+            if let Some(due) = entry.due_date() {
+                map.entry(due).or_default().push(entry);
+            }
+        }
+        map
+    }
+
+    /// Homework due on `date`, e.g. `due_on(&homework, today.succ_opt().unwrap())` for "due tomorrow"
+    pub fn due_on(homework: &[Homework], date: NaiveDate) -> Vec<&Homework> {
+        homework.iter().filter(|entry| entry.due_date() == Some(date)).collect()
+    }
+
+    pub type Result = Vec<Homework>;
+}
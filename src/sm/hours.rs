@@ -22,6 +22,11 @@ pub mod request {
     pub struct SmHoursRequestParams<'s> {
     	pub action: SmHoursRequestAction<'s>
     }
+    impl Default for SmHoursRequestParams<'_> {
+    	fn default() -> Self {
+    		Self::new()
+    	}
+    }
     impl SmHoursRequestParams<'_> {
     	pub fn new() -> Self {
     		Self {
@@ -46,6 +51,11 @@ pub mod request {
     	params: SmHoursRequestParams<'l>,
     	result: Option<super::response::Result>
     }
+    impl Default for Hours<'_> {
+    	fn default() -> Self {
+    		Self::new()
+    	}
+    }
     impl Hours<'_> {
     	pub fn new() -> Self {
     		Self {
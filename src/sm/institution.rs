@@ -0,0 +1,76 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde_json::Value;
+
+    /// Metadata of the school the account belongs to
+    #[derive(Debug, Default)]
+    pub struct Institution {
+        result: Option<super::response::Result>
+    }
+    impl Institution {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Institution {
+        fn get_module_name(&self) -> &'static str {"institution"}
+        fn get_endpoint_name(&self) -> &'static str {"get-institution"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing institution")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_institution(&mut self, institution: &'l mut Institution) -> ERes<()> {
+            self._state.push(institution);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use super::ModRequest;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Address {
+        pub street: Option<String>,
+        pub zip_code: Option<String>,
+        pub city: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Institution {
+        pub id: usize,
+        pub name: String,
+        pub address: Option<Address>,
+        pub logo_url: Option<String>,
+        /// names of the modules enabled for this school, as used by `ModRequest::get_module_name`
+        #[serde(default)]
+        pub modules: Vec<String>
+    }
+    impl Institution {
+        pub fn has_module(&self, module: &str) -> bool {
+            self.modules.iter().any(|enabled| enabled == module)
+        }
+        /// Whether the school enabled the module `request` belongs to, so it can be sent without failing.
+        pub fn supports(&self, request: &dyn ModRequest) -> bool {
+            self.has_module(request.get_module_name())
+        }
+    }
+
+    pub type Result = Institution;
+}
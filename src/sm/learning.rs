@@ -0,0 +1,266 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct Courses {
+        result: Option<super::response::Result>
+    }
+    impl Courses {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Courses {
+        fn get_module_name(&self) -> &'static str {"learning"}
+        fn get_endpoint_name(&self) -> &'static str {"get-courses"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing courses")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CourseParams {
+        pub course_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct CourseEntries {
+        params: CourseParams,
+        result: Option<Vec<super::response::Entry>>
+    }
+    impl CourseEntries {
+        pub fn new(course_id: usize) -> Self {
+            Self {
+                params: CourseParams {
+                    course_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Entry>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for CourseEntries {
+        fn get_module_name(&self) -> &'static str {"learning"}
+        fn get_endpoint_name(&self) -> &'static str {"get-course-entries"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing course entries")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Assignments {
+        result: Option<Vec<super::response::Assignment>>
+    }
+    impl Assignments {
+        /// Requests the assignments of all courses
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Assignment>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Assignments {
+        fn get_module_name(&self) -> &'static str {"learning"}
+        fn get_endpoint_name(&self) -> &'static str {"get-assignments"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing assignments")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitAssignmentParams {
+        pub assignment_id: usize,
+        pub student_id: usize,
+        pub file_ids: Vec<usize>,
+        pub comment: Option<String>
+    }
+
+    #[derive(Debug)]
+    pub struct SubmitAssignment {
+        params: SubmitAssignmentParams,
+        result: Option<super::response::Submission>
+    }
+    impl SubmitAssignment {
+        /// Hands in the previously uploaded `files` (see `Schulmanager::upload_submission_file`).
+        pub fn new(assignment_id: usize, student_id: usize, files: &[super::response::SubmissionFile], comment: Option<String>) -> Self {
+            Self {
+                params: SubmitAssignmentParams {
+                    assignment_id,
+                    student_id,
+                    file_ids: files.iter().map(|file| file.id).collect(),
+                    comment
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Submission> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for SubmitAssignment {
+        fn get_module_name(&self) -> &'static str {"learning"}
+        fn get_endpoint_name(&self) -> &'static str {"submit-assignment"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing assignment submission")?);
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_courses(&mut self, courses: &'l mut Courses) -> ERes<()> {
+            self._state.push(courses);
+            Ok(())
+        }
+        pub fn add_course_entries(&mut self, entries: &'l mut CourseEntries) -> ERes<()> {
+            self._state.push(entries);
+            Ok(())
+        }
+        pub fn add_assignments(&mut self, assignments: &'l mut Assignments) -> ERes<()> {
+            self._state.push(assignments);
+            Ok(())
+        }
+        pub fn add_submit_assignment(&mut self, submit: &'l mut SubmitAssignment) -> ERes<()> {
+            self._state.push(submit);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::sm::calendar::response::parse_datetime;
+    use crate::sm::timetable::response::Subject;
+    use serde::Deserialize;
+    use chrono::NaiveDateTime;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CourseTeacher {
+        pub id: usize,
+        pub abbreviation: Option<String>,
+        pub firstname: Option<String>,
+        pub lastname: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Course {
+        pub id: usize,
+        pub name: String,
+        pub subject: Option<Subject>,
+        #[serde(default)]
+        pub teachers: Vec<CourseTeacher>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct File {
+        pub id: usize,
+        pub filename: String,
+        pub mime_type: Option<String>,
+        pub size: Option<u64>
+    }
+
+    /// File uploaded for a submission, returned by `Schulmanager::upload_submission_file`
+    pub type SubmissionFile = File;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Submission {
+        pub id: usize,
+        pub submitted_at: String,
+        pub comment: Option<String>,
+        #[serde(default)]
+        pub files: Vec<File>,
+        /// teacher feedback once the submission got corrected
+        pub feedback: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Assignment {
+        pub id: usize,
+        pub course_id: usize,
+        pub title: String,
+        pub description: Option<String>,
+        pub deadline: Option<String>,
+        #[serde(default)]
+        pub files: Vec<File>,
+        pub submission: Option<Submission>
+    }
+    impl Assignment {
+        pub fn deadline(&self) -> Option<NaiveDateTime> {
+            self.deadline.as_deref().and_then(parse_datetime)
+        }
+        /// Whether the deadline passed at `now` without anything being handed in
+        pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+            self.submission.is_none() && self.deadline().map(|deadline| deadline < now).unwrap_or(false)
+        }
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    pub enum EntryKind {
+        Material {
+            #[serde(default)]
+            files: Vec<File>,
+            link: Option<String>
+        },
+        Assignment { assignment: Assignment },
+        /// an entry type this crate doesn't know yet
+        #[serde(other)]
+        Other
+    }
+
+    /// Single item of a course's timeline
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Entry {
+        pub id: usize,
+        pub title: String,
+        pub text: Option<String>,
+        pub created_at: String,
+        #[serde(flatten)]
+        pub kind: EntryKind
+    }
+
+    /// Open assignments (nothing handed in yet) ordered by deadline; those without a deadline come last.
+    pub fn open_by_deadline(assignments: &[Assignment]) -> Vec<&Assignment> {
+        let mut open: Vec<&Assignment> = assignments.iter().filter(|assignment| assignment.submission.is_none()).collect();
+        open.sort_by_key(|assignment| (assignment.deadline().is_none(), assignment.deadline()));
+        open
+    }
+
+    pub type Result = Vec<Course>;
+}
@@ -0,0 +1,271 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct Letters {
+        result: Option<super::response::Result>
+    }
+    impl Letters {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Letters {
+        fn get_module_name(&self) -> &'static str {"letters"}
+        fn get_endpoint_name(&self) -> &'static str {"get-letters"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing letters")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct LetterParams {
+        pub id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Letter {
+        params: LetterParams,
+        result: Option<super::response::Letter>
+    }
+    impl Letter {
+        pub fn new(id: usize) -> Self {
+            Self {
+                params: LetterParams {
+                    id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Letter> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Letter {
+        fn get_module_name(&self) -> &'static str {"letters"}
+        fn get_endpoint_name(&self) -> &'static str {"get-letter"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing letter")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConfirmLetterParams {
+        pub letter_id: usize,
+        pub student_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct ConfirmLetter {
+        params: ConfirmLetterParams,
+        confirmed: bool
+    }
+    impl ConfirmLetter {
+        pub fn new(letter_id: usize, student_id: usize) -> Self {
+            Self {
+                params: ConfirmLetterParams {
+                    letter_id,
+                    student_id
+                },
+                confirmed: false
+            }
+        }
+        pub fn get(&mut self) -> ERes<()> {
+            if self.confirmed {
+                Ok(())
+            } else {
+                Err(crate::errors::SmError::UninitializedData.into())
+            }
+        }
+    }
+    impl ModRequest for ConfirmLetter {
+        fn get_module_name(&self) -> &'static str {"letters"}
+        fn get_endpoint_name(&self) -> &'static str {"confirm-letter"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, _value: Value) -> ERes<()> {
+            self.confirmed = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitFormParams {
+        pub letter_id: usize,
+        pub student_id: usize,
+        pub answers: Vec<super::response::FormFieldAnswer>
+    }
+
+    #[derive(Debug)]
+    pub struct SubmitForm {
+        params: SubmitFormParams,
+        submitted: bool
+    }
+    impl SubmitForm {
+        /// Validates `answers` against `form` before anything gets sent.
+        pub fn new(letter_id: usize, student_id: usize, form: &super::response::Form, answers: Vec<super::response::FormFieldAnswer>) -> ERes<Self> {
+            form.validate(&answers)?;
+            Ok(Self {
+                params: SubmitFormParams {
+                    letter_id,
+                    student_id,
+                    answers
+                },
+                submitted: false
+            })
+        }
+        pub fn get(&mut self) -> ERes<()> {
+            if self.submitted {
+                Ok(())
+            } else {
+                Err(crate::errors::SmError::UninitializedData.into())
+            }
+        }
+    }
+    impl ModRequest for SubmitForm {
+        fn get_module_name(&self) -> &'static str {"letters"}
+        fn get_endpoint_name(&self) -> &'static str {"submit-response"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, _value: Value) -> ERes<()> {
+            self.submitted = true;
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_letters(&mut self, letters: &'l mut Letters) -> ERes<()> {
+            self._state.push(letters);
+            Ok(())
+        }
+        pub fn add_letter(&mut self, letter: &'l mut Letter) -> ERes<()> {
+            self._state.push(letter);
+            Ok(())
+        }
+        pub fn add_confirm_letter(&mut self, confirm: &'l mut ConfirmLetter) -> ERes<()> {
+            self._state.push(confirm);
+            Ok(())
+        }
+        pub fn add_submit_form(&mut self, submit: &'l mut SubmitForm) -> ERes<()> {
+            self._state.push(submit);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::errors::SmError;
+    use serde::{Serialize, Deserialize};
+    pub use super::super::forms::{FormOption, FormFieldKind, FormField, FormAnswer};
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StudentStatus {
+        pub student_id: usize,
+        pub read_timestamp: Option<String>,
+        pub confirmation_timestamp: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Attachment {
+        pub id: usize,
+        pub filename: String,
+        pub mime_type: Option<String>,
+        pub size: Option<u64>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Sender {
+        pub id: usize,
+        pub firstname: Option<String>,
+        pub lastname: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LetterSummary {
+        pub id: usize,
+        pub title: String,
+        pub sending_timestamp: Option<String>,
+        #[serde(default)]
+        pub requires_confirmation: bool,
+        pub sender: Option<Sender>,
+        #[serde(default)]
+        pub student_statuses: Vec<StudentStatus>
+    }
+    impl LetterSummary {
+        fn status(&self, student_id: usize) -> Option<&StudentStatus> {
+            self.student_statuses.iter().find(|status| status.student_id == student_id)
+        }
+        pub fn is_read(&self, student_id: usize) -> bool {
+            self.status(student_id).map(|status| status.read_timestamp.is_some()).unwrap_or(false)
+        }
+        pub fn is_confirmed(&self, student_id: usize) -> bool {
+            self.status(student_id).map(|status| status.confirmation_timestamp.is_some()).unwrap_or(false)
+        }
+    }
+
+    /// Reply form (Rückmeldung) attached to a letter
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Form {
+        pub id: usize,
+        pub fields: Vec<FormField>
+    }
+    impl Form {
+        pub fn validate(&self, answers: &[FormFieldAnswer]) -> std::result::Result<(), SmError> {
+            super::super::forms::validate(&self.fields, answers.iter().map(|answer| (answer.field_id, &answer.value)))
+        }
+    }
+
+    #[derive(Serialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FormFieldAnswer {
+        pub field_id: usize,
+        #[serde(flatten)]
+        pub value: FormAnswer
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Letter {
+        pub id: usize,
+        pub title: String,
+        /// HTML formatted body of the letter
+        pub content: String,
+        pub sending_timestamp: Option<String>,
+        #[serde(default)]
+        pub requires_confirmation: bool,
+        pub sender: Option<Sender>,
+        #[serde(default)]
+        pub attachments: Vec<Attachment>,
+        pub form: Option<Form>,
+        #[serde(default)]
+        pub student_statuses: Vec<StudentStatus>
+    }
+
+    pub type Result = Vec<LetterSummary>;
+}
@@ -6,6 +6,7 @@ pub struct Student {
     pub id: usize,
     pub firstname: Option<String>,
     pub lastname: Option<String>,
+    #[allow(dead_code)]
     sex: Option<String>,
     pub class_id: usize
 }
@@ -17,8 +18,9 @@ pub struct User {
     pub username: Option<String>,
     pub local_username: Option<String>,
     pub id: usize,
+    #[allow(dead_code)]
     has_administrator_rights: bool,
-    last_seen_notification_timestamp: Option<String>,
+    pub last_seen_notification_timestamp: Option<String>,
     pub firstname: Option<String>,
     pub lastname: Option<String>,
     pub associated_student: Student
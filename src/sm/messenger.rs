@@ -0,0 +1,332 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct Subscriptions {
+        result: Option<super::response::Result>
+    }
+    impl Subscriptions {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Subscriptions {
+        fn get_module_name(&self) -> &'static str {"messenger"}
+        fn get_endpoint_name(&self) -> &'static str {"get-subscriptions"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing messenger subscriptions")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MessagesParams {
+        pub subscription_id: usize,
+        pub offset: usize,
+        pub limit: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Messages {
+        params: MessagesParams,
+        result: Option<Vec<super::response::Message>>
+    }
+    impl Messages {
+        /// Requests up to `limit` messages of a thread, skipping the `offset` newest ones; `limit` has to be at least 1.
+        pub fn new(subscription_id: usize, offset: usize, limit: usize) -> ERes<Self> {
+            if limit == 0 {
+                return Err(crate::errors::SmError::InvalidPageLimit.into())
+            }
+            Ok(Self {
+                params: MessagesParams {
+                    subscription_id,
+                    offset,
+                    limit
+                },
+                result: None
+            })
+        }
+        /// The server only sends the messages themselves, without a total or continuation token, so a
+        /// page is assumed to have more after it whenever it is full (`limit` messages).
+        pub fn get(&mut self) -> ERes<super::response::MessagePage> {
+            let messages = self.result.take().ok_or(crate::errors::SmError::UninitializedData)?;
+            Ok(super::response::MessagePage {
+                has_more: messages.len() >= self.params.limit,
+                next_offset: self.params.offset + messages.len(),
+                messages
+            })
+        }
+    }
+    impl ModRequest for Messages {
+        fn get_module_name(&self) -> &'static str {"messenger"}
+        fn get_endpoint_name(&self) -> &'static str {"get-messages-by-subscription"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing messages")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RecipientParams {
+        pub id: usize,
+        pub r#type: super::response::RecipientType
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(untagged)]
+    pub enum SendMessageParams {
+        #[serde(rename_all = "camelCase")]
+        Reply {
+            thread_id: usize,
+            subscription_id: usize,
+            text: String
+        },
+        #[serde(rename_all = "camelCase")]
+        NewThread {
+            subject: String,
+            text: String,
+            recipients: Vec<RecipientParams>
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SendMessage {
+        params: SendMessageParams,
+        result: Option<super::response::Message>
+    }
+    impl SendMessage {
+        pub fn reply(thread_id: usize, subscription_id: usize, text: String) -> Self {
+            Self {
+                params: SendMessageParams::Reply {
+                    thread_id,
+                    subscription_id,
+                    text
+                },
+                result: None
+            }
+        }
+        pub fn new_thread(subject: String, text: String, recipients: &[super::response::Recipient]) -> Self {
+            Self {
+                params: SendMessageParams::NewThread {
+                    subject,
+                    text,
+                    recipients: recipients.iter().map(|recipient| RecipientParams {
+                        id: recipient.id,
+                        r#type: recipient.r#type
+                    }).collect()
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Message> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for SendMessage {
+        fn get_module_name(&self) -> &'static str {"messenger"}
+        fn get_endpoint_name(&self) -> &'static str {
+            match self.params {
+                SendMessageParams::Reply { .. } => "send-response",
+                SendMessageParams::NewThread { .. } => "send-message"
+            }
+        }
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing sent message")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MarkAsReadParams {
+        pub subscription_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct MarkAsRead {
+        params: MarkAsReadParams,
+        marked: bool
+    }
+    impl MarkAsRead {
+        pub fn new(subscription_id: usize) -> Self {
+            Self {
+                params: MarkAsReadParams {
+                    subscription_id
+                },
+                marked: false
+            }
+        }
+        pub fn get(&mut self) -> ERes<()> {
+            if self.marked {
+                Ok(())
+            } else {
+                Err(crate::errors::SmError::UninitializedData.into())
+            }
+        }
+    }
+    impl ModRequest for MarkAsRead {
+        fn get_module_name(&self) -> &'static str {"messenger"}
+        fn get_endpoint_name(&self) -> &'static str {"mark-subscription-as-read"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, _value: Value) -> ERes<()> {
+            self.marked = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Recipients {
+        result: Option<Vec<super::response::Recipient>>
+    }
+    impl Recipients {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Recipient>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Recipients {
+        fn get_module_name(&self) -> &'static str {"messenger"}
+        fn get_endpoint_name(&self) -> &'static str {"get-possible-recipients"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing messenger recipients")?);
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_subscriptions(&mut self, subscriptions: &'l mut Subscriptions) -> ERes<()> {
+            self._state.push(subscriptions);
+            Ok(())
+        }
+        pub fn add_messages(&mut self, messages: &'l mut Messages) -> ERes<()> {
+            self._state.push(messages);
+            Ok(())
+        }
+        pub fn add_send_message(&mut self, message: &'l mut SendMessage) -> ERes<()> {
+            self._state.push(message);
+            Ok(())
+        }
+        pub fn add_mark_as_read(&mut self, mark: &'l mut MarkAsRead) -> ERes<()> {
+            self._state.push(mark);
+            Ok(())
+        }
+        pub fn add_recipients(&mut self, recipients: &'l mut Recipients) -> ERes<()> {
+            self._state.push(recipients);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Participant {
+        pub id: usize,
+        pub firstname: Option<String>,
+        pub lastname: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Thread {
+        pub id: usize,
+        pub subject: String,
+        pub sender: Option<Participant>,
+        pub recipient_string: Option<String>,
+        #[serde(default)]
+        pub allow_answers: bool
+    }
+
+    /// A conversation the user takes part in
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Subscription {
+        pub id: usize,
+        pub thread: Thread,
+        #[serde(default)]
+        pub unread_count: usize,
+        pub last_message_timestamp: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MessageAttachment {
+        pub id: usize,
+        pub filename: String,
+        pub mime_type: Option<String>
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Message {
+        pub id: usize,
+        pub text: String,
+        pub sender: Option<Participant>,
+        pub send_date: String,
+        #[serde(default)]
+        pub attachments: Vec<MessageAttachment>
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MessagePage {
+        pub messages: Vec<Message>,
+        /// offset to request the next (older) page with
+        pub next_offset: usize,
+        /// whether the page was full; the next page may still turn out empty
+        pub has_more: bool
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum RecipientType {
+        Teacher,
+        Parent,
+        Student,
+        Group,
+        /// a recipient type this crate doesn't know yet; such recipients can't be messaged
+        #[serde(other, skip_serializing)]
+        Unknown
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Recipient {
+        pub id: usize,
+        pub r#type: RecipientType,
+        pub name: String
+    }
+
+    pub type Result = Vec<Subscription>;
+}
@@ -5,6 +5,37 @@ pub mod timetable;
 pub use timetable::{request::Timetable, response::Result as TimetableResult};
 pub mod hours;
 pub use hours::{request::Hours, response::Result as HoursResult};
+pub mod letters;
+pub use letters::request::{Letters, Letter, ConfirmLetter, SubmitForm};
+pub mod messenger;
+pub use messenger::request::{Subscriptions, Messages, SendMessage, MarkAsRead, Recipients};
+pub mod calendar;
+pub use calendar::{request::Calendar, response::Result as CalendarResult};
+pub mod exams;
+pub use exams::{request::Exams, response::Result as ExamsResult};
+pub mod homework;
+pub use homework::{request::Homework, response::Result as HomeworkResult};
+pub mod sick_notes;
+pub use sick_notes::{request::{SubmitSickNote, SickNotes, AbsenceReport, AbsencePeriod}, response::Result as SickNotesResult};
+pub mod absences;
+pub use absences::{request::Absences, response::Result as AbsencesResult};
+pub mod grades;
+pub use grades::{request::Grades, response::Result as GradesResult};
+pub mod consultation;
+pub use consultation::{request::{ConsultationEvents, Slots, Bookings, BookSlot, CancelBooking}, response::Result as ConsultationEventsResult};
+pub mod notifications;
+pub use notifications::{request::Counters, response::Result as CountersResult};
+pub mod forms;
+pub mod surveys;
+pub use surveys::{request::{Surveys, Survey, SubmitSurvey, Submissions}, response::Result as SurveysResult};
+pub mod learning;
+pub use learning::{request::{Courses, CourseEntries, Assignments, SubmitAssignment}, response::Result as CoursesResult};
+pub mod institution;
+pub use institution::{request::Institution, response::Result as InstitutionResult};
+pub mod directory;
+pub use directory::{request::{Teachers, Classes, StudentGroups}, response::{Directory, Result as TeachersResult}};
+pub mod substitutions;
+pub use substitutions::{request::Substitutions, response::Result as SubstitutionsResult};
 
 use crate::errors::SmError;
 use serde::{Serialize, Deserialize};
@@ -28,6 +59,38 @@ impl <'s> Request<'s> {
 	}
 }
 
+/// Bundle version sent if it couldn't be discovered from the web app
+pub const FALLBACK_BUNDLE_VERSION: &str = "fee1dead";
+
+/// Extracts the bundle version (the hash of the `main` script) from the web app's index page.
+pub fn parse_bundle_version(html: &str) -> Option<String> {
+	html.match_indices("main").find_map(|(idx, _)| {
+		let rest = &html[idx + "main".len()..];
+		/* angular builds may add a target suffix, e.g. main-es2015.<hash>.js */
+		let rest = match rest.strip_prefix('-') {
+			Some(rest) => rest.trim_start_matches(|c: char| c.is_ascii_alphanumeric()),
+			None => rest
+		};
+		let rest = rest.strip_prefix('.')?;
+		let hash_len = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+		let (hash, rest) = rest.split_at(hash_len);
+		if hash.len() >= 8 && rest.starts_with(".js") {
+			Some(hash.to_owned())
+		} else {
+			None
+		}
+	})
+}
+
+/// Whether `/api/calls` rejected a request because of an outdated bundle version, flagged by `outdatedBundleVersion`.
+pub fn is_outdated_bundle(body: &str) -> bool {
+	let body: Value = match serde_json::from_str(body) {
+		Ok(body) => body,
+		Err(_) => return false
+	};
+	body.get("outdatedBundleVersion").and_then(Value::as_bool).unwrap_or(false)
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestBody<'s> {
@@ -35,9 +98,9 @@ pub struct RequestBody<'s> {
 	pub requests: Vec<Request<'s>>
 }
 impl <'s> RequestBody<'s> {
-	fn new(requests: &Vec<&'s mut dyn ModRequest>) -> ERes<Self> {
+	fn new(requests: &[&mut dyn ModRequest], bundle_version: &'s str) -> ERes<Self> {
 		Ok(Self {
-			bundle_version: "fee1dead",
+			bundle_version,
 			requests: requests.iter().map(|req| Request::new(*req)).collect::<ERes<Vec<Request<'s>>>>()?
 		})
 	}
@@ -49,11 +112,56 @@ pub struct Result {
 	pub data: Value
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	Info,
+	Warning,
+	Error,
+	#[serde(other)]
+	Unknown
+}
+
+/// Maintenance notice or outage report sent along with the results of a call
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatusMessage {
+	pub id: Option<usize>,
+	#[serde(default = "SystemStatusMessage::default_severity")]
+	pub severity: Severity,
+	pub text: String,
+	pub valid_from: Option<String>,
+	pub valid_until: Option<String>
+}
+impl SystemStatusMessage {
+	fn default_severity() -> Severity {
+		Severity::Info
+	}
+	pub fn valid_from(&self) -> Option<chrono::NaiveDateTime> {
+		self.valid_from.as_deref().and_then(calendar::response::parse_datetime)
+	}
+	pub fn valid_until(&self) -> Option<chrono::NaiveDateTime> {
+		self.valid_until.as_deref().and_then(calendar::response::parse_datetime)
+	}
+	/// Whether the message applies at `now`; open ends count as always valid
+	pub fn is_active(&self, now: chrono::NaiveDateTime) -> bool {
+		self.valid_from().map(|from| from <= now).unwrap_or(true)
+			&& self.valid_until().map(|until| now <= until).unwrap_or(true)
+	}
+}
+
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct ResultBody {
 	pub results: Vec<Result>,
-	#[serde(default)]
-	pub system_status_messages: Vec<Value>
+	/// entries in an unexpected shape are dropped, so they can't fail the whole call
+	#[serde(default, deserialize_with = "deserialize_status_messages")]
+	pub system_status_messages: Vec<SystemStatusMessage>
+}
+
+fn deserialize_status_messages<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<SystemStatusMessage>, D::Error> {
+	let values: Vec<Value> = Deserialize::deserialize(deserializer)?;
+	Ok(values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect())
 }
 
 pub type ParseRes<T> = std::result::Result<T, serde_json::Error>;
@@ -68,6 +176,11 @@ pub trait ModRequest {
 pub struct RequestManager<'l> {
 	pub _state: Vec<&'l mut dyn ModRequest>
 }
+impl Default for RequestManager<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
 impl <'l, 'r> RequestManager<'l> {
 	pub fn new() -> Self {
 		Self {
@@ -75,17 +188,25 @@ impl <'l, 'r> RequestManager<'l> {
 		}
 	}
 
-	pub fn get_request(&'r self) -> ERes<RequestBody> {
-		RequestBody::new(&self._state)
+	/// The body of a call sending `FALLBACK_BUNDLE_VERSION`, see `get_request_with_bundle_version`
+	pub fn get_request(&'r self) -> ERes<RequestBody<'r>> {
+		self.get_request_with_bundle_version(FALLBACK_BUNDLE_VERSION)
+	}
+
+	pub fn get_request_with_bundle_version(&'r self, bundle_version: &'r str) -> ERes<RequestBody<'r>> {
+		RequestBody::new(&self._state, bundle_version)
 	}
 
-	pub fn get_results(&'l mut self, mut result: ResultBody) -> ERes<()> {
-		self._state.iter_mut().enumerate().map(|(i, request)| -> ERes<()> {
-			let status = result.results[i].status;
-			if status < 200 || status >= 300 {
-				return Err(SmError::NonvalidStatusCode { statuscode: status }.into())
+	pub fn get_results(&mut self, result: ResultBody) -> ERes<()> {
+		if result.results.len() < self._state.len() {
+			return Err(SmError::NoData.into())
+		}
+		/* results come back in request order; pair them up instead of removing by index, which shifts the remaining ones */
+		self._state.iter_mut().zip(result.results).try_for_each(|(request, result)| -> ERes<()> {
+			if !(200..300).contains(&result.status) {
+				return Err(SmError::NonvalidStatusCode { statuscode: result.status }.into())
 			}
-			request.set_value(serde_json::from_value(result.results.remove(i).data)?)
-		}).collect()
+			request.set_value(result.data)
+		})
 	}
 }
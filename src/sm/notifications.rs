@@ -0,0 +1,75 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde_json::Value;
+
+    /// Unread counters of all modules; cheap enough to be polled or batched with other calls
+    #[derive(Debug, Default)]
+    pub struct Counters {
+        result: Option<super::response::Result>
+    }
+    impl Counters {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Counters {
+        fn get_module_name(&self) -> &'static str {"notifications"}
+        fn get_endpoint_name(&self) -> &'static str {"get-unread-counts"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing unread counters")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_counters(&mut self, counters: &'l mut Counters) -> ERes<()> {
+            self._state.push(counters);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use serde::Deserialize;
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    /// Keys of `Counters::other` that count unread items of further modules, named like the modules
+    pub const COUNTER_KEYS: &[&str] = &["surveys", "learning", "grades", "calendar", "consultation-days"];
+
+    #[derive(Deserialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Counters {
+        #[serde(default)]
+        pub letters: usize,
+        #[serde(default)]
+        pub messages: usize,
+        #[serde(default)]
+        pub notifications: usize,
+        /// counters of any further modules keyed by module name, plus whatever else the endpoint sends
+        #[serde(flatten)]
+        pub other: BTreeMap<String, Value>
+    }
+    impl Counters {
+        /// Sum of all counters; only the entries of `other` listed in `COUNTER_KEYS` are counted
+        pub fn total(&self) -> usize {
+            self.letters + self.messages + self.notifications
+                + COUNTER_KEYS.iter()
+                    .filter_map(|key| self.other.get(*key).and_then(Value::as_u64))
+                    .map(|count| count as usize)
+                    .sum::<usize>()
+        }
+    }
+
+    pub type Result = Counters;
+}
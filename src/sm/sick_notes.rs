@@ -0,0 +1,192 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use crate::errors::SmError;
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+    use chrono::{NaiveDate, Datelike, Weekday};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum AbsencePeriod {
+        /// whole days from `from` until `until` (both inclusive)
+        Days { from: NaiveDate, until: NaiveDate },
+        /// the class hours `from_hour` to `until_hour` (both inclusive) of a single day
+        Hours { date: NaiveDate, from_hour: usize, until_hour: usize }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AbsenceReport {
+        pub student_id: usize,
+        pub period: AbsencePeriod,
+        pub reason: String,
+        pub comment: Option<String>
+    }
+    impl AbsenceReport {
+        /// Checks the report against `today` without contacting the server.
+        pub fn validate(&self, today: NaiveDate) -> std::result::Result<(), SmError> {
+            let (from, until) = match self.period {
+                AbsencePeriod::Days { from, until } => (from, until),
+                AbsencePeriod::Hours { date, from_hour, until_hour } => {
+                    if from_hour > until_hour {
+                        return Err(SmError::InvalidAbsencePeriod)
+                    }
+                    (date, date)
+                }
+            };
+            if from > until {
+                return Err(SmError::InvalidAbsencePeriod)
+            }
+            if from < today {
+                return Err(SmError::AbsenceInPast { date: from })
+            }
+            for date in [from, until] {
+                if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                    return Err(SmError::AbsenceOnWeekend { date })
+                }
+            }
+            if self.reason.trim().is_empty() {
+                return Err(SmError::MissingAbsenceReason)
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitSickNoteParams {
+        pub student_id: usize,
+        pub start_date: String,
+        pub end_date: String,
+        pub from_class_hour: Option<usize>,
+        pub until_class_hour: Option<usize>,
+        pub reason: String,
+        pub comment: Option<String>
+    }
+    impl From<AbsenceReport> for SubmitSickNoteParams {
+        fn from(report: AbsenceReport) -> Self {
+            let (start, end, from_class_hour, until_class_hour) = match report.period {
+                AbsencePeriod::Days { from, until } => (from, until, None, None),
+                AbsencePeriod::Hours { date, from_hour, until_hour } => (date, date, Some(from_hour), Some(until_hour))
+            };
+            Self {
+                student_id: report.student_id,
+                start_date: start.format("%F").to_string(),
+                end_date: end.format("%F").to_string(),
+                from_class_hour,
+                until_class_hour,
+                reason: report.reason,
+                comment: report.comment
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SubmitSickNote {
+        params: SubmitSickNoteParams,
+        result: Option<super::response::SickNote>
+    }
+    impl SubmitSickNote {
+        /// Validates `report` (see `AbsenceReport::validate`) before anything gets sent.
+        pub fn new(report: AbsenceReport, today: NaiveDate) -> ERes<Self> {
+            report.validate(today)?;
+            Ok(Self {
+                params: report.into(),
+                result: None
+            })
+        }
+        pub fn get(&mut self) -> ERes<super::response::SickNote> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for SubmitSickNote {
+        fn get_module_name(&self) -> &'static str {"absences"}
+        fn get_endpoint_name(&self) -> &'static str {"submit-sick-note"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing submitted sick note")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SickNotesParams {
+        pub student_id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct SickNotes {
+        params: SickNotesParams,
+        result: Option<super::response::Result>
+    }
+    impl SickNotes {
+        pub fn new(student_id: usize) -> Self {
+            Self {
+                params: SickNotesParams {
+                    student_id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for SickNotes {
+        fn get_module_name(&self) -> &'static str {"absences"}
+        fn get_endpoint_name(&self) -> &'static str {"get-sick-notes"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing sick notes")?);
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_submit_sick_note(&mut self, note: &'l mut SubmitSickNote) -> ERes<()> {
+            self._state.push(note);
+            Ok(())
+        }
+        pub fn add_sick_notes(&mut self, notes: &'l mut SickNotes) -> ERes<()> {
+            self._state.push(notes);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum SickNoteStatus {
+        Pending,
+        Accepted,
+        Rejected,
+        #[serde(other)]
+        Unknown
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SickNote {
+        pub id: usize,
+        pub student_id: usize,
+        pub start_date: String,
+        pub end_date: String,
+        pub from_class_hour: Option<usize>,
+        pub until_class_hour: Option<usize>,
+        pub reason: String,
+        pub comment: Option<String>,
+        pub status: SickNoteStatus,
+        pub created_at: Option<String>
+    }
+
+    pub type Result = Vec<SickNote>;
+}
@@ -0,0 +1,59 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+    use chrono::NaiveDate;
+
+    #[derive(Serialize, Debug)]
+    pub struct SubstitutionsParams {
+        pub start: String,
+        pub end: String
+    }
+
+    /// Substitutions and cancellations of the whole school, not just the logged in student
+    #[derive(Debug)]
+    pub struct Substitutions {
+        params: SubstitutionsParams,
+        result: Option<super::response::Result>
+    }
+    impl Substitutions {
+        /// Requests the substitution plan between `start` and `end` (both inclusive).
+        pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+            Self {
+                params: SubstitutionsParams {
+                    start: start.format("%F").to_string(),
+                    end: end.format("%F").to_string()
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Substitutions {
+        fn get_module_name(&self) -> &'static str {"schedules"}
+        fn get_endpoint_name(&self) -> &'static str {"get-substitutions"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing substitutions")?);
+            Ok(())
+        }
+    }
+    impl <'l> RequestManager<'l> {
+        pub fn add_substitutions(&mut self, substitutions: &'l mut Substitutions) -> ERes<()> {
+            self._state.push(substitutions);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    /// same entries as the timetable, see `transformers::smartv2::SubstitutionPlan` for grouping them by class
+    pub type Result = crate::sm::timetable::response::Result;
+}
@@ -0,0 +1,214 @@
+pub use super::{ModRequest, ParseRes, RequestManager};
+
+pub mod request {
+    use super::{ModRequest, ParseRes, RequestManager};
+    use anyhow::{Result as ERes, Context};
+    use serde::Serialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Default)]
+    pub struct Surveys {
+        result: Option<super::response::Result>
+    }
+    impl Surveys {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Result> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Surveys {
+        fn get_module_name(&self) -> &'static str {"surveys"}
+        fn get_endpoint_name(&self) -> &'static str {"get-open-surveys"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing surveys")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct SurveyParams {
+        pub id: usize
+    }
+
+    #[derive(Debug)]
+    pub struct Survey {
+        params: SurveyParams,
+        result: Option<super::response::Survey>
+    }
+    impl Survey {
+        pub fn new(id: usize) -> Self {
+            Self {
+                params: SurveyParams {
+                    id
+                },
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<super::response::Survey> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Survey {
+        fn get_module_name(&self) -> &'static str {"surveys"}
+        fn get_endpoint_name(&self) -> &'static str {"get-survey"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing survey")?);
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitSurveyParams {
+        pub survey_id: usize,
+        pub student_id: usize,
+        pub answers: Vec<super::response::QuestionAnswer>
+    }
+
+    #[derive(Debug)]
+    pub struct SubmitSurvey {
+        params: SubmitSurveyParams,
+        submitted: bool
+    }
+    impl SubmitSurvey {
+        /// Validates `answers` against the questions of `survey` before anything gets sent.
+        pub fn new(survey: &super::response::Survey, student_id: usize, answers: Vec<super::response::QuestionAnswer>) -> ERes<Self> {
+            survey.validate(&answers)?;
+            Ok(Self {
+                params: SubmitSurveyParams {
+                    survey_id: survey.id,
+                    student_id,
+                    answers
+                },
+                submitted: false
+            })
+        }
+        pub fn get(&mut self) -> ERes<()> {
+            if self.submitted {
+                Ok(())
+            } else {
+                Err(crate::errors::SmError::UninitializedData.into())
+            }
+        }
+    }
+    impl ModRequest for SubmitSurvey {
+        fn get_module_name(&self) -> &'static str {"surveys"}
+        fn get_endpoint_name(&self) -> &'static str {"submit-survey"}
+        fn get_value(&self) -> ParseRes<Value> {
+            serde_json::to_value(&self.params)
+        }
+        fn set_value(&mut self, _value: Value) -> ERes<()> {
+            self.submitted = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Submissions {
+        result: Option<Vec<super::response::Submission>>
+    }
+    impl Submissions {
+        pub fn new() -> Self {
+            Self {
+                result: None
+            }
+        }
+        pub fn get(&mut self) -> ERes<Vec<super::response::Submission>> {
+            self.result.take().ok_or(crate::errors::SmError::UninitializedData.into())
+        }
+    }
+    impl ModRequest for Submissions {
+        fn get_module_name(&self) -> &'static str {"surveys"}
+        fn get_endpoint_name(&self) -> &'static str {"get-submissions"}
+        fn get_value(&self) -> ParseRes<Value> {
+            Ok(Value::Object(Default::default()))
+        }
+        fn set_value(&mut self, value: Value) -> ERes<()> {
+            self.result = Some(serde_json::from_value(value).context("failed parsing survey submissions")?);
+            Ok(())
+        }
+    }
+
+    impl <'l> RequestManager<'l> {
+        pub fn add_surveys(&mut self, surveys: &'l mut Surveys) -> ERes<()> {
+            self._state.push(surveys);
+            Ok(())
+        }
+        pub fn add_survey(&mut self, survey: &'l mut Survey) -> ERes<()> {
+            self._state.push(survey);
+            Ok(())
+        }
+        pub fn add_submit_survey(&mut self, submit: &'l mut SubmitSurvey) -> ERes<()> {
+            self._state.push(submit);
+            Ok(())
+        }
+        pub fn add_submissions(&mut self, submissions: &'l mut Submissions) -> ERes<()> {
+            self._state.push(submissions);
+            Ok(())
+        }
+    }
+}
+
+pub mod response {
+    use crate::errors::SmError;
+    use serde::{Serialize, Deserialize};
+    pub use super::super::forms::{FormOption, FormFieldKind, FormField, FormAnswer};
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SurveySummary {
+        pub id: usize,
+        pub title: String,
+        pub deadline: Option<String>,
+        #[serde(default)]
+        pub submitted: bool
+    }
+
+    /// Survey questions are form fields, their `text` ends up in `label`
+    pub type Question = FormField;
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Survey {
+        pub id: usize,
+        pub title: String,
+        pub description: Option<String>,
+        pub deadline: Option<String>,
+        pub questions: Vec<Question>
+    }
+    impl Survey {
+        pub fn validate(&self, answers: &[QuestionAnswer]) -> std::result::Result<(), SmError> {
+            super::super::forms::validate(&self.questions, answers.iter().map(|answer| (answer.question_id, &answer.value)))
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QuestionAnswer {
+        pub question_id: usize,
+        #[serde(flatten)]
+        pub value: FormAnswer
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Submission {
+        pub id: usize,
+        pub survey_id: usize,
+        pub survey_title: String,
+        pub submitted_at: String,
+        pub answers: Vec<QuestionAnswer>
+    }
+
+    pub type Result = Vec<SurveySummary>;
+}
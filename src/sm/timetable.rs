@@ -5,12 +5,13 @@ pub mod request {
 	use anyhow::{Result as ERes, Context};
     use serde::Serialize;
     use serde_json::Value;
-    use chrono::{Date, Weekday, Datelike, Local, TimeZone};
+    use chrono::{NaiveDate, Weekday, Datelike, Local};
     /*  Thanks to harmic for his brilliant stackoverflow answer
     https://stackoverflow.com/questions/64174950/get-date-of-start-end-of-week */
-    fn week_bounds(week: u32, year: i32) -> (Date<Local>, Date<Local>) {
-        let mon: Date<Local> = Local.isoywd(year, week, Weekday::Mon);
-        let sun: Date<Local> = Local.isoywd(year, week, Weekday::Sun);
+    /* panics on weeks the year doesn't have, just like the deprecated `Local.isoywd` did */
+    fn week_bounds(week: u32, year: i32) -> (NaiveDate, NaiveDate) {
+        let mon = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).expect("invalid iso week");
+        let sun = NaiveDate::from_isoywd_opt(year, week, Weekday::Sun).expect("invalid iso week");
         (mon, sun)
     }
 
@@ -33,8 +34,8 @@ pub mod request {
             let (mon, sun) = week_bounds(week, year);
     		Self {
     			student: TimetableBodyParamsStudent {
-                    id: id,
-                    class_id: class_id
+                    id,
+                    class_id
                 },
                 start: mon.format("%F").to_string(),
                 end: sun.format("%F").to_string()
@@ -101,6 +102,7 @@ pub mod response {
     #[derive(Deserialize, Debug, Clone)]
     pub struct Teacher {
         pub id: usize,
+        #[serde(default)]
         pub abbreviation: String,
         pub firstname: Option<String>,
         pub lastname: Option<String>
@@ -109,6 +111,7 @@ pub mod response {
     #[derive(Deserialize, Debug, Clone)]
     pub struct Class {
         pub id: usize,
+        #[serde(default)]
         pub name: String
     }
 
@@ -116,6 +119,7 @@ pub mod response {
     #[serde(rename_all = "camelCase")]
     pub struct StudentGroup {
         pub id: usize,
+        #[serde(default)]
         pub name: String,
         pub class_id: Option<usize>
     }
@@ -124,10 +128,13 @@ pub mod response {
     #[serde(rename_all = "camelCase")]
     pub struct Event {
         pub text: String,
+        #[serde(default)]
         pub teachers: Vec<Teacher>,
+        #[serde(default)]
         pub classes: Vec<Class>,
+        #[serde(default)]
         pub student_groups: Vec<StudentGroup>,
-        pub absence_id: usize
+        pub absence_id: Option<usize>
     }
 
     #[derive(Deserialize, Debug, Clone)]
@@ -0,0 +1,135 @@
+use super::smartv2::{DayMap, TimetableElement, Lesson, Event, Teacher};
+use crate::SchoolHoursMap;
+use std::io::{self, Write};
+use chrono::{NaiveDate, Datelike};
+
+const HEADER: [&str; 15] = [
+    "date", "weekday", "hour", "start", "end", "status",
+    "subject", "room", "teachers", "classes", "groups", "comment",
+    "original_subject", "original_room", "original_teachers"
+];
+
+/// Exports a `DayMap` as CSV with one record per timetable element.
+///
+/// The output is UTF-8; by default it is prefixed with a byte order mark so spreadsheet
+/// applications pick up umlauts correctly, and fields that would be read as formulas are defused.
+#[derive(Clone, Debug)]
+pub struct CsvExporter<'h> {
+    delimiter: char,
+    bom: bool,
+    formula_escape: bool,
+    hours: Option<&'h SchoolHoursMap>
+}
+impl Default for CsvExporter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl <'h> CsvExporter<'h> {
+    pub fn new() -> Self {
+        Self {
+            delimiter: ',',
+            bom: true,
+            formula_escape: true,
+            hours: None
+        }
+    }
+    /// Use another field delimiter, e.g. `;` for spreadsheet applications set to a german locale.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+    pub fn with_bom(mut self, bom: bool) -> Self {
+        self.bom = bom;
+        self
+    }
+    /// Prefix fields starting with `=`, `+`, `-` or `@` with `'`, so spreadsheet applications show
+    /// them as text instead of evaluating them. Comments and names come from school staff, don't
+    /// turn this off for files that get opened in a spreadsheet.
+    pub fn with_formula_escape(mut self, formula_escape: bool) -> Self {
+        self.formula_escape = formula_escape;
+        self
+    }
+    /// Fill the start and end columns from `hours` (see `SmHours::parse`).
+    pub fn with_hours(mut self, hours: &'h SchoolHoursMap) -> Self {
+        self.hours = Some(hours);
+        self
+    }
+
+    fn escape(&self, field: &str) -> String {
+        let field = if self.formula_escape && field.starts_with(&['=', '+', '-', '@'][..]) {
+            format!("'{}", field)
+        } else {
+            field.to_owned()
+        };
+        if field.contains(self.delimiter) || field.contains(&['"', '\n', '\r'][..]) || field.starts_with(' ') || field.ends_with(' ') {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field
+        }
+    }
+
+    fn write_record<W: Write>(&self, writer: &mut W, record: &[&str]) -> io::Result<()> {
+        let record: Vec<String> = record.iter().map(|field| self.escape(field)).collect();
+        write!(writer, "{}\r\n", record.join(&self.delimiter.to_string()))
+    }
+
+    fn times(&self, date: NaiveDate, from_hour: usize, until_hour: usize) -> (String, String) {
+        let day = date.weekday().num_days_from_monday() as usize;
+        let time = |hour: usize| self.hours.and_then(|hours| hours.get(&hour)).and_then(|times| times.get(day)).copied();
+        (
+            time(from_hour).map(|(start, _)| start.format("%H:%M").to_string()).unwrap_or_default(),
+            time(until_hour).map(|(_, end)| end.format("%H:%M").to_string()).unwrap_or_default()
+        )
+    }
+
+    fn write_event<W: Write>(&self, writer: &mut W, date: NaiveDate, event: &Event) -> io::Result<()> {
+        let (start, end) = self.times(date, event.from_hour, event.until_hour);
+        let hour = if event.from_hour == event.until_hour {
+            event.from_hour.to_string()
+        } else {
+            format!("{}-{}", event.from_hour, event.until_hour)
+        };
+        self.write_record(writer, &[
+            &date.format("%F").to_string(), &date.format("%A").to_string(), &hour, &start, &end, "event",
+            &event.text, "", &Teacher::abbreviations(&event.teachers), &event.classes.join(", "), &event.student_groups.join(", "), "",
+            "", "", ""
+        ])
+    }
+
+    fn write_element<W: Write>(&self, writer: &mut W, date: NaiveDate, hour: usize, element: &TimetableElement) -> io::Result<()> {
+        let (status, lesson, original): (&str, &Lesson, Option<&Lesson>) = match element {
+            TimetableElement::Lesson(lesson) => ("lesson", lesson, None),
+            TimetableElement::Substitution(actual, original) => ("substitution", actual, Some(original)),
+            TimetableElement::Cancelled(lesson) => ("cancelled", lesson, None),
+            TimetableElement::Event(event) => return self.write_event(writer, date, event)
+        };
+        let (start, end) = self.times(date, hour, hour);
+        self.write_record(writer, &[
+            &date.format("%F").to_string(), &date.format("%A").to_string(), &hour.to_string(), &start, &end, status,
+            &lesson.subject.name, &lesson.room, &Teacher::abbreviations(&lesson.teachers), &lesson.classes.join(", "), &lesson.student_groups.join(", "), lesson.comment.as_deref().unwrap_or_default(),
+            original.map(|orig| orig.subject.name.as_str()).unwrap_or_default(), original.map(|orig| orig.room.as_str()).unwrap_or_default(), &original.map(|orig| Teacher::abbreviations(&orig.teachers)).unwrap_or_default()
+        ])
+    }
+
+    pub fn write_daymap<W: Write>(&self, daymap: &DayMap, mut writer: W) -> io::Result<()> {
+        if self.bom {
+            writer.write_all("\u{feff}".as_bytes())?;
+        }
+        self.write_record(&mut writer, &HEADER)?;
+        for (date, day) in daymap.map.iter() {
+            for (hour, elements) in day {
+                for element in elements {
+                    self.write_element(&mut writer, *date, *hour, element)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn export_daymap(&self, daymap: &DayMap) -> String {
+        let mut out = Vec::new();
+        self.write_daymap(daymap, &mut out).expect("writing to a Vec does not fail");
+        String::from_utf8(out).expect("the exporter only writes UTF-8")
+    }
+}
@@ -0,0 +1,53 @@
+use crate::sm::homework::response::Homework;
+use chrono::{DateTime, Utc};
+
+/* RFC 5545 3.3.11 */
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/* RFC 5545 3.1: lines should not be longer than 75 octets */
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Exports `homework` as an iCalendar document with one VTODO per entry, so it can be synced into task apps.
+pub fn homework_to_vtodo(homework: &[Homework], dtstamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    out.push_str(&fold("BEGIN:VCALENDAR"));
+    out.push_str(&fold("VERSION:2.0"));
+    out.push_str(&fold("PRODID:-//libschulmanager//homework//DE"));
+    for entry in homework {
+        let summary = entry.homework.lines().next().unwrap_or_default();
+        out.push_str(&fold("BEGIN:VTODO"));
+        out.push_str(&fold(&format!("UID:homework-{}@schulmanager-online.de", entry.id)));
+        out.push_str(&fold(&format!("DTSTAMP:{}", dtstamp.format("%Y%m%dT%H%M%SZ"))));
+        out.push_str(&fold(&format!("SUMMARY:{}", escape(&format!("{}: {}", entry.subject.name, summary)))));
+        out.push_str(&fold(&format!("DESCRIPTION:{}", escape(&entry.homework))));
+        out.push_str(&fold(&format!("CATEGORIES:{}", escape(&entry.subject.name))));
+        if let Some(date) = entry.date() {
+            out.push_str(&fold(&format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d"))));
+        }
+        if let Some(due) = entry.due_date() {
+            out.push_str(&fold(&format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d"))));
+        }
+        out.push_str(&fold("END:VTODO"));
+    }
+    out.push_str(&fold("END:VCALENDAR"));
+    out
+}
@@ -2,8 +2,19 @@
 pub mod smartv1;
 #[cfg(feature = "smartv2")]
 pub mod smartv2;
+#[cfg(feature = "smartv2")]
+pub mod render;
+#[cfg(feature = "smartv2")]
+pub mod csv;
+pub mod ical;
 
 impl crate::SmTimetable {
+    /// Fills teacher, class and student group names the timetable is missing from `directory`
+    /// (see `Schulmanager::get_directory`) before transforming it.
+    pub fn with_directory(mut self, directory: &crate::sm::Directory) -> Self {
+        directory.complete_timetable(&mut self.interna_timetable);
+        self
+    }
 	#[cfg(feature = "smartv1")]
     pub fn to_smart_v1(self) -> Result<smartv1::SmWeek, Box<dyn std::error::Error>> {
         smartv1::SmWeek::from_interna(self.interna_timetable)
@@ -16,4 +27,8 @@ impl crate::SmTimetable {
     pub fn to_smart_v2_daymap(self) -> Result<smartv2::DayMap, Box<dyn std::error::Error>> {
         smartv2::DayMap::from_interna(self.interna_timetable)
     }
+    #[cfg(feature = "smartv2")]
+    pub fn to_csv(self, exporter: &csv::CsvExporter) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(exporter.export_daymap(&self.to_smart_v2_daymap()?))
+    }
 }
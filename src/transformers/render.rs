@@ -0,0 +1,255 @@
+use super::smartv2::{DayMap, Weekdays, TimetableElement, Lesson, Event, Teacher};
+use crate::SchoolHoursMap;
+use std::collections::BTreeMap;
+use chrono::{NaiveDate, Weekday, Datelike};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    German,
+    English
+}
+impl Language {
+    fn weekday(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Language::German => ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+            Language::English => ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"]
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+    fn header(self) -> [&'static str; 6] {
+        match self {
+            Language::German => ["Std.", "Zeit", "Fach", "Raum", "Lehrkraft", "Info"],
+            Language::English => ["Hour", "Time", "Subject", "Room", "Teacher", "Info"]
+        }
+    }
+    fn cancelled(self) -> &'static str {
+        match self {
+            Language::German => "entfällt",
+            Language::English => "cancelled"
+        }
+    }
+    fn substitution(self) -> &'static str {
+        match self {
+            Language::German => "Vertretung",
+            Language::English => "substitution"
+        }
+    }
+    fn event(self) -> &'static str {
+        match self {
+            Language::German => "Veranstaltung",
+            Language::English => "event"
+        }
+    }
+    fn date(self, date: NaiveDate) -> String {
+        match self {
+            Language::German => format!("{}, {}", self.weekday(date.weekday()), date.format("%d.%m.%Y")),
+            Language::English => format!("{}, {}", self.weekday(date.weekday()), date.format("%F"))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Markdown,
+    Html
+}
+/* characters with a meaning in (GitHub flavoured) Markdown tables and inline formatting */
+const MARKDOWN_SPECIAL: [char; 11] = ['\\', '`', '*', '_', '[', ']', '#', '|', '~', '<', '>'];
+
+impl Format {
+    fn escape(self, text: &str) -> String {
+        match self {
+            Format::Text => text.to_owned(),
+            Format::Markdown => {
+                let mut escaped = String::with_capacity(text.len());
+                /* a newline would end the table row, turn it into a line break inside the cell */
+                for (i, line) in text.lines().enumerate() {
+                    if i > 0 {
+                        escaped.push_str("<br>");
+                    }
+                    for c in line.chars() {
+                        if MARKDOWN_SPECIAL.contains(&c) {
+                            escaped.push('\\');
+                        }
+                        escaped.push(c);
+                    }
+                }
+                escaped
+            },
+            Format::Html => text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+        }
+    }
+    fn strike(self, text: &str) -> String {
+        match self {
+            Format::Text => text.to_owned(),
+            Format::Markdown => format!("~~{}~~", text),
+            Format::Html => format!("<del>{}</del>", text)
+        }
+    }
+}
+
+struct Row {
+    hour: String,
+    time: String,
+    cells: [String; 4]
+}
+
+/* formats "old → new" if the value changed and just the value otherwise; expects escaped input */
+fn changed(old: String, new: String) -> String {
+    if old == new {
+        new
+    } else {
+        format!("{} → {}", old, new)
+    }
+}
+
+/// Renders the smartv2 timetable representations into human-readable text, Markdown or HTML.
+#[derive(Clone, Debug)]
+pub struct Renderer<'h> {
+    format: Format,
+    language: Language,
+    hours: Option<&'h SchoolHoursMap>
+}
+impl <'h> Renderer<'h> {
+    pub fn new(format: Format, language: Language) -> Self {
+        Self {
+            format,
+            language,
+            hours: None
+        }
+    }
+    /// Use `hours` (see `SmHours::parse`) to show the start and end time of every class hour.
+    pub fn with_hours(mut self, hours: &'h SchoolHoursMap) -> Self {
+        self.hours = Some(hours);
+        self
+    }
+
+    pub fn render_daymap(&self, daymap: &DayMap) -> String {
+        daymap.map.iter()
+            .map(|(date, day)| self.render_day(&self.language.date(*date), date.weekday(), day))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn render_weekdays(&self, week: &Weekdays) -> String {
+        [(Weekday::Mon, &week.monday), (Weekday::Tue, &week.tuesday), (Weekday::Wed, &week.wednesday), (Weekday::Thu, &week.thursday), (Weekday::Fri, &week.friday)].iter()
+            .filter(|(_, day)| !day.is_empty())
+            .map(|(weekday, day)| self.render_day(self.language.weekday(*weekday), *weekday, day))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Renders a single day of a `DayMap` or `Weekdays` with `title` as its heading.
+    pub fn render_day(&self, title: &str, weekday: Weekday, day: &BTreeMap<usize, Vec<TimetableElement>>) -> String {
+        let rows: Vec<Row> = day.iter()
+            .flat_map(|(hour, elements)| elements.iter().map(move |element| (*hour, element)))
+            .map(|(hour, element)| self.row(hour, weekday, element))
+            .collect();
+        let title = self.format.escape(title);
+        let header = self.language.header();
+        match self.format {
+            Format::Text => {
+                let mut out = format!("{}\n", title);
+                for row in rows {
+                    let columns: Vec<&str> = [row.time.as_str(), &row.cells[0], &row.cells[1], &row.cells[2], &row.cells[3]].iter()
+                        .copied()
+                        .filter(|cell| !cell.is_empty())
+                        .collect();
+                    out.push_str(&format!("{:>6} {}\n", row.hour, columns.join("  ")));
+                }
+                out
+            },
+            Format::Markdown => {
+                let mut out = format!("### {}\n\n| {} |\n|{}\n", title, header.join(" | "), "---|".repeat(header.len()));
+                for row in rows {
+                    out.push_str(&format!("| {} | {} | {} |\n", row.hour, row.time, row.cells.join(" | ")));
+                }
+                out
+            },
+            Format::Html => {
+                let mut out = format!("<h3>{}</h3>\n<table>\n<tr>", title);
+                for cell in header.iter() {
+                    out.push_str(&format!("<th>{}</th>", cell));
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str(&format!("<tr><td>{}</td><td>{}</td>", row.hour, row.time));
+                    for cell in row.cells.iter() {
+                        out.push_str(&format!("<td>{}</td>", cell));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+                out
+            }
+        }
+    }
+
+    fn time(&self, from_hour: usize, until_hour: usize, weekday: Weekday) -> String {
+        let day = weekday.num_days_from_monday() as usize;
+        let hours = match self.hours {
+            Some(hours) => hours,
+            None => return String::new()
+        };
+        match (hours.get(&from_hour).and_then(|times| times.get(day)), hours.get(&until_hour).and_then(|times| times.get(day))) {
+            (Some((from, _)), Some((_, until))) => format!("{}–{}", from.format("%H:%M"), until.format("%H:%M")),
+            _ => String::new()
+        }
+    }
+
+    fn lesson_cells(&self, lesson: &Lesson) -> [String; 3] {
+        [self.format.escape(&lesson.subject.name), self.format.escape(&lesson.room), self.format.escape(&Teacher::abbreviations(&lesson.teachers))]
+    }
+
+    fn comment(&self, comment: &Option<String>, status: &str) -> String {
+        match comment {
+            Some(comment) if status.is_empty() => self.format.escape(comment),
+            Some(comment) => format!("{}: {}", status, self.format.escape(comment)),
+            None => status.to_owned()
+        }
+    }
+
+    fn row(&self, hour: usize, weekday: Weekday, element: &TimetableElement) -> Row {
+        match element {
+            TimetableElement::Lesson(lesson) => {
+                let [subject, room, teachers] = self.lesson_cells(lesson);
+                Row {
+                    hour: format!("{}.", hour),
+                    time: self.time(hour, hour, weekday),
+                    cells: [subject, room, teachers, self.comment(&lesson.comment, "")]
+                }
+            },
+            TimetableElement::Substitution(actual, original) => {
+                let [subject, room, teachers] = self.lesson_cells(actual);
+                let [orig_subject, orig_room, orig_teachers] = self.lesson_cells(original);
+                Row {
+                    hour: format!("{}.", hour),
+                    time: self.time(hour, hour, weekday),
+                    cells: [changed(orig_subject, subject), changed(orig_room, room), changed(orig_teachers, teachers), self.comment(&actual.comment, self.language.substitution())]
+                }
+            },
+            TimetableElement::Cancelled(lesson) => {
+                let [subject, room, teachers] = self.lesson_cells(lesson);
+                Row {
+                    hour: format!("{}.", hour),
+                    time: self.time(hour, hour, weekday),
+                    cells: [self.format.strike(&subject), self.format.strike(&room), self.format.strike(&teachers), self.comment(&lesson.comment, self.language.cancelled())]
+                }
+            },
+            TimetableElement::Event(event) => self.event_row(weekday, event)
+        }
+    }
+
+    fn event_row(&self, weekday: Weekday, event: &Event) -> Row {
+        Row {
+            hour: if event.from_hour == event.until_hour {
+                format!("{}.", event.from_hour)
+            } else {
+                format!("{}.–{}.", event.from_hour, event.until_hour)
+            },
+            time: self.time(event.from_hour, event.until_hour, weekday),
+            cells: [self.format.escape(&event.text), String::new(), self.format.escape(&Teacher::abbreviations(&event.teachers)), self.language.event().to_owned()]
+        }
+    }
+}
@@ -74,26 +74,26 @@ impl SmLesson {
     pub fn from_actual(lesson: ActualLesson, status: SmLessonStatus, comment: Option<String>) -> Self {
         let (classes, student_groups) = string_vec_calc(lesson.classes, lesson.student_groups);
         SmLesson {
-            status: status,
+            status,
             room: lesson.room.name,
             subject: SmSubject::new(lesson.subject),
             teachers: SmTeacher::new_vec(lesson.teachers),
-            classes: classes,
-            student_groups: student_groups,
-            comment: comment,
+            classes,
+            student_groups,
+            comment,
             subject_label: lesson.subject_label
         }
     }
     pub fn from_orig(lesson: OriginalLesson, status: SmLessonStatus, comment: Option<String>) -> Self {
         let (classes, student_groups) = string_vec_calc(lesson.classes, lesson.student_groups);
         SmLesson {
-            status: status,
+            status,
             room: lesson.room.name,
             subject: SmSubject::new(lesson.subject),
             teachers: SmTeacher::new_vec(lesson.teachers),
-            classes: classes,
-            student_groups: student_groups,
-            comment: comment,
+            classes,
+            student_groups,
+            comment,
             subject_label: lesson.subject_label
         }
     }
@@ -133,9 +133,9 @@ impl SmSubstitutedLesson {
             room: lesson.room.name,
             subject: SmSubject::new(lesson.subject),
             teachers: SmTeacher::new_vec(lesson.teachers),
-            classes: classes,
-            student_groups: student_groups,
-            comment: comment,
+            classes,
+            student_groups,
+            comment,
             subject_label: lesson.subject_label
         }
     }
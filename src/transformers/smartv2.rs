@@ -1,6 +1,8 @@
-use crate::sm::timetable::response::{Result, ActualLesson as InternaActualLesson, OriginalLesson as InternaOriginalLesson, Subject as InternaSubject, Teacher as InternaTeacher, Class as InternaClass, StudentGroup as InternaStudentGroup, Event as InternaEvent};
+use crate::sm::timetable::response::{Result, Datum, ActualLesson as InternaActualLesson, OriginalLesson as InternaOriginalLesson, Subject as InternaSubject, Teacher as InternaTeacher, Class as InternaClass, StudentGroup as InternaStudentGroup, Event as InternaEvent};
+use crate::sm::calendar::response::CalendarEvent;
+use crate::SchoolHoursMap;
 use std::collections::BTreeMap;
-use chrono::{Weekday, NaiveDate, Datelike};
+use chrono::{Weekday, NaiveDate, NaiveTime, Datelike};
 use serde::Serialize;
 
 fn string_vec_calc(classes_s: Vec<InternaClass>, groups: Vec<InternaStudentGroup>) -> (Vec<String>, Vec<String>) {
@@ -15,7 +17,23 @@ fn string_vec_calc(classes_s: Vec<InternaClass>, groups: Vec<InternaStudentGroup
     (classes, student_groups)
 }
 
+fn id_vec_calc(classes: &[InternaClass], groups: &[InternaStudentGroup]) -> (Vec<ClassId>, Vec<StudentGroupId>) {
+    (classes.iter().map(|class| ClassId(class.id)).collect(), groups.iter().map(|group| StudentGroupId(group.id)).collect())
+}
+
+macro_rules! typed_id {
+    ($($name:ident),*) => {
+        $(
+            #[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[serde(transparent)]
+            pub struct $name(pub usize);
+        )*
+    };
+}
+typed_id!(SubjectId, TeacherId, RoomId, ClassId, StudentGroupId, LessonId, SubstitutionId);
+
 #[derive(Serialize, Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum TimetableElement {
     Lesson(Lesson),
     Substitution(Lesson, Lesson),
@@ -25,12 +43,14 @@ pub enum TimetableElement {
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Subject {
+    pub id: SubjectId,
     pub abbreviation: String,
     pub name: String
 }
 impl Subject {
     pub fn new(subj: InternaSubject) -> Self {
         Subject {
+            id: SubjectId(subj.id),
             abbreviation: subj.abbreviation,
             name: subj.name
         }
@@ -39,6 +59,7 @@ impl Subject {
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Teacher {
+    pub id: TeacherId,
     pub abbreviation: String,
     pub firstname: Option<String>,
     pub lastname: Option<String>
@@ -46,6 +67,7 @@ pub struct Teacher {
 impl Teacher {
     pub fn new(teacher: InternaTeacher) -> Self {
         Teacher {
+            id: TeacherId(teacher.id),
             abbreviation: teacher.abbreviation,
             firstname: teacher.firstname,
             lastname: teacher.lastname
@@ -58,41 +80,62 @@ impl Teacher {
         }
         teachers
     }
+    /// Comma separated abbreviations of `teachers`, as shown in exports and rendered timetables
+    pub fn abbreviations(teachers: &[Teacher]) -> String {
+        teachers.iter().map(|teacher| teacher.abbreviation.as_str()).collect::<Vec<&str>>().join(", ")
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Lesson {
     pub room: String,
+    pub room_id: RoomId,
     pub subject: Subject,
     pub teachers: Vec<Teacher>,
     pub classes: Vec<String>,
+    pub class_ids: Vec<ClassId>,
     pub student_groups: Vec<String>,
+    pub student_group_ids: Vec<StudentGroupId>,
     pub comment: Option<String>,
     pub subject_label: String,
+    pub lesson_id: Option<LessonId>,
+    pub substitution_id: Option<SubstitutionId>
 }
 impl Lesson {
     pub fn from_actual(lesson: InternaActualLesson, comment: Option<String>) -> Self {
+        let (class_ids, student_group_ids) = id_vec_calc(&lesson.classes, &lesson.student_groups);
         let (classes, student_groups) = string_vec_calc(lesson.classes, lesson.student_groups);
         Lesson {
             room: lesson.room.name,
+            room_id: RoomId(lesson.room.id),
             subject: Subject::new(lesson.subject),
             teachers: Teacher::new_vec(lesson.teachers),
             classes,
+            class_ids,
             student_groups,
+            student_group_ids,
             comment,
-            subject_label: lesson.subject_label
+            subject_label: lesson.subject_label,
+            lesson_id: lesson.lesson_id.map(LessonId),
+            substitution_id: lesson.substitution_id.map(SubstitutionId)
         }
     }
     pub fn from_orig(lesson: InternaOriginalLesson, comment: Option<String>) -> Self {
+        let (class_ids, student_group_ids) = id_vec_calc(&lesson.classes, &lesson.student_groups);
         let (classes, student_groups) = string_vec_calc(lesson.classes, lesson.student_groups);
         Lesson {
             room: lesson.room.name,
+            room_id: RoomId(lesson.room.id),
             subject: Subject::new(lesson.subject),
             teachers: Teacher::new_vec(lesson.teachers),
             classes,
+            class_ids,
             student_groups,
+            student_group_ids,
             comment,
-            subject_label: lesson.subject_label
+            subject_label: lesson.subject_label,
+            lesson_id: Some(LessonId(lesson.lesson_id)),
+            substitution_id: None
         }
     }
     pub fn from_orig_vec(lessons: &Vec<InternaOriginalLesson>, comment: Option<String>) -> Option<Self> {
@@ -104,21 +147,76 @@ impl Lesson {
     }
 }
 
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    /// the classes are absent from school (e.g. an excursion), see `Event::absence_id`
+    ClassAbsence,
+    /// merged from the school calendar, see `DayMap::merge_calendar`
+    Calendar,
+    Other
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct Event {
     pub text: String,
     pub teachers: Vec<Teacher>,
     pub classes: Vec<String>,
-    pub student_groups: Vec<String>
+    pub class_ids: Vec<ClassId>,
+    pub student_groups: Vec<String>,
+    pub student_group_ids: Vec<StudentGroupId>,
+    /// see `absences::response::Result::for_event`
+    pub absence_id: Option<usize>,
+    pub event_type: EventType,
+    /// first class hour covered by this event
+    pub from_hour: usize,
+    /// last class hour covered by this event
+    pub until_hour: usize,
+    /// the event covers every class hour of its day, see `DayMap::mark_all_day`
+    pub all_day: bool
 }
 impl Event {
-    pub fn new(event: InternaEvent) -> Self {
+    pub fn new(event: InternaEvent, hour: usize) -> Self {
+        let (class_ids, student_group_ids) = id_vec_calc(&event.classes, &event.student_groups);
         let (classes, student_groups) = string_vec_calc(event.classes, event.student_groups);
         Event {
             text: event.text,
             teachers: Teacher::new_vec(event.teachers),
             classes,
-            student_groups
+            class_ids,
+            student_groups,
+            student_group_ids,
+            absence_id: event.absence_id,
+            event_type: match event.absence_id {
+                Some(_) => EventType::ClassAbsence,
+                None => EventType::Other
+            },
+            from_hour: hour,
+            until_hour: hour,
+            all_day: false
+        }
+    }
+    pub fn from_calendar(event: &CalendarEvent, from_hour: usize, until_hour: usize) -> Self {
+        let (class_ids, student_group_ids) = id_vec_calc(&event.classes, &event.student_groups);
+        let (classes, student_groups) = string_vec_calc(event.classes.clone(), event.student_groups.clone());
+        Event {
+            text: event.summary.clone(),
+            teachers: vec![],
+            classes,
+            class_ids,
+            student_groups,
+            student_group_ids,
+            absence_id: None,
+            event_type: EventType::Calendar,
+            from_hour,
+            until_hour,
+            all_day: event.all_day
+        }
+    }
+    /// whether `other` describes the same event, just in another class hour
+    pub fn is_same(&self, other: &Event) -> bool {
+        match (self.absence_id, other.absence_id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ => self.text == other.text && self.class_ids == other.class_ids && self.student_group_ids == other.student_group_ids
         }
     }
 }
@@ -144,38 +242,157 @@ fn check_treemap(map: &mut BTreeMap<usize, Vec<TimetableElement>>, key: usize, v
     };
 }
 
+/* merges events spanning several consecutive class hours into a single element placed at their first hour */
+fn group_events(day: BTreeMap<usize, Vec<TimetableElement>>) -> BTreeMap<usize, Vec<TimetableElement>> {
+    let mut grouped: BTreeMap<usize, Vec<TimetableElement>> = BTreeMap::new();
+    for (hour, elements) in day {
+        for element in elements {
+            if let TimetableElement::Event(event) = &element {
+                let previous = grouped.values_mut().flatten().find_map(|tte| match tte {
+                    TimetableElement::Event(prev) if prev.until_hour + 1 == hour && prev.is_same(event) => Some(prev),
+                    _ => None
+                });
+                if let Some(previous) = previous {
+                    previous.until_hour = hour;
+                    continue;
+                }
+            }
+            check_treemap(&mut grouped, hour, element);
+        }
+    }
+    grouped
+}
+
+fn classify(ilesson: Datum) -> Option<TimetableElement> {
+    if let Some(event) = ilesson.event {
+        let hour = ilesson.class_hour.number.parse().ok()?;
+        Some(TimetableElement::Event(Event::new(event, hour)))
+    } else if ilesson.is_substitution.unwrap_or(false) {
+        Some(TimetableElement::Substitution(Lesson::from_actual(ilesson.actual_lesson?, ilesson.comment.clone()), Lesson::from_orig_vec(ilesson.original_lessons.as_ref()?, ilesson.comment)?))
+    } else if ilesson.is_cancelled.unwrap_or(false) {
+        Some(TimetableElement::Cancelled(Lesson::from_orig_vec(ilesson.original_lessons.as_ref()?, ilesson.comment)?))
+    } else {
+        Some(TimetableElement::Lesson(Lesson::from_actual(ilesson.actual_lesson?, ilesson.comment)))
+    }
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct DayMap {
     pub map: BTreeMap<NaiveDate, BTreeMap<usize, Vec<TimetableElement>>>
 }
 impl DayMap {
     pub fn from_interna(interna_timetable: Result) -> std::result::Result<Self, Box<dyn std::error::Error>> {
-        let mut map = BTreeMap::new();
+        let mut map: BTreeMap<NaiveDate, BTreeMap<usize, Vec<TimetableElement>>> = BTreeMap::new();
         for ilesson in interna_timetable {
-            #[allow(unused_assignments)]
-            let mut tte: Option<TimetableElement> = None;
-            if ilesson.is_new.is_some() {
-                tte = Some(TimetableElement::Event(Event::new(skip_none!(ilesson.event))))
-            } else if ilesson.is_substitution.is_some() {
-                tte = Some(TimetableElement::Substitution(Lesson::from_actual(skip_none!(ilesson.actual_lesson), ilesson.comment.clone()), skip_none!(Lesson::from_orig_vec(skip_none!(&ilesson.original_lessons), ilesson.comment))));
-            } else if ilesson.is_cancelled.is_some() {
-                tte = Some(TimetableElement::Cancelled(skip_none!(Lesson::from_orig_vec(skip_none!(&ilesson.original_lessons), ilesson.comment.clone()))));
-            } else {
-                tte = Some(TimetableElement::Lesson(Lesson::from_actual(skip_none!(ilesson.actual_lesson), ilesson.comment.clone())));
-            }
             let date = NaiveDate::parse_from_str(&ilesson.date, "%F")?;
-            match map.get_mut(&date) {
-                Some(val) => check_treemap(val, ilesson.class_hour.number.parse()?, skip_none!(tte)),
-                None => {
-                    map.insert(date, BTreeMap::new());
-                    check_treemap(map.get_mut(&date).unwrap(), ilesson.class_hour.number.parse()?, skip_none!(tte))
-                }
-            }
+            let hour: usize = ilesson.class_hour.number.parse()?;
+            let tte = skip_none!(classify(ilesson));
+            check_treemap(map.entry(date).or_default(), hour, tte);
         }
         Ok(DayMap {
-            map
+            map: map.into_iter().map(|(date, day)| (date, group_events(day))).collect()
         })
     }
+
+    /// Adds calendar entries addressed to `class_id` (see `CalendarEvent::targets_class`) as `TimetableElement::Event`s,
+    /// placed at the class hours (see `SmHours::parse`) they overlap.
+    /// Entries outside of the dates already covered by this map are ignored unless the map is empty.
+    pub fn merge_calendar(&mut self, events: &[CalendarEvent], hours: &SchoolHoursMap, class_id: usize) {
+        let bounds = match (self.map.keys().next(), self.map.keys().next_back()) {
+            (Some(first), Some(last)) => Some((*first, *last)),
+            _ => None
+        };
+        for event in events.iter().filter(|event| event.targets_class(class_id)) {
+            let (start, end) = match (event.start(), event.end()) {
+                (Some(start), Some(end)) => (start, end),
+                (Some(start), None) => (start, start),
+                _ => continue
+            };
+            for date in event.dates() {
+                if let Some((first, last)) = bounds {
+                    if date < first || date > last {
+                        continue;
+                    }
+                }
+                let day = date.weekday().num_days_from_monday() as usize;
+                let day_start = if date == start.date() && !event.all_day { start.time() } else { NaiveTime::MIN };
+                let day_end = if date == end.date() && !event.all_day { end.time() } else { NaiveTime::from_hms_opt(23, 59, 59).unwrap() };
+                let covered: Vec<usize> = hours.iter()
+                    .filter_map(|(hour, times)| times.get(day).map(|times| (*hour, times)))
+                    .filter(|(_, (from, until))| *from < day_end && *until > day_start)
+                    .map(|(hour, _)| hour)
+                    .collect();
+                if let (Some(from_hour), Some(until_hour)) = (covered.first(), covered.last()) {
+                    let mut element = Event::from_calendar(event, *from_hour, *until_hour);
+                    element.all_day |= covers_day(&element, day, hours);
+                    check_treemap(self.map.entry(date).or_default(), *from_hour, TimetableElement::Event(element));
+                }
+            }
+        }
+    }
+
+    /// Sets `all_day` on every event spanning all class hours the school has on that weekday.
+    /// Timetable data alone doesn't tell how many hours a day has, so this needs the parsed `SmHours`.
+    pub fn mark_all_day(&mut self, hours: &SchoolHoursMap) {
+        for (date, day) in self.map.iter_mut() {
+            let weekday = date.weekday().num_days_from_monday() as usize;
+            for element in day.values_mut().flatten() {
+                if let TimetableElement::Event(event) = element {
+                    event.all_day |= covers_day(event, weekday, hours);
+                }
+            }
+        }
+    }
+}
+
+/* whether the event spans every class hour that exists on `weekday` (0 = monday) */
+fn covers_day(event: &Event, weekday: usize, hours: &SchoolHoursMap) -> bool {
+    let day_hours: Vec<usize> = hours.iter()
+        .filter(|(_, times)| times.get(weekday).is_some())
+        .map(|(hour, _)| *hour)
+        .collect();
+    match (day_hours.first(), day_hours.last()) {
+        (Some(first), Some(last)) => event.from_hour <= *first && event.until_hour >= *last,
+        _ => false
+    }
+}
+
+/// School-wide substitution plan: substitutions and cancellations grouped by date, class and class hour.
+///
+/// Elements concerning several classes are listed under each of them. Elements without any class are
+/// listed under their student groups, or under an empty class name if they have neither.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SubstitutionPlan {
+    pub map: BTreeMap<NaiveDate, BTreeMap<String, BTreeMap<usize, Vec<TimetableElement>>>>
+}
+impl SubstitutionPlan {
+    pub fn from_interna(interna_plan: Result) -> std::result::Result<Self, Box<dyn std::error::Error>> {
+        let mut plan = Self::default();
+        for ilesson in interna_plan {
+            let date = NaiveDate::parse_from_str(&ilesson.date, "%F")?;
+            let hour: usize = ilesson.class_hour.number.parse()?;
+            let tte = skip_none!(classify(ilesson));
+            let lesson = match &tte {
+                TimetableElement::Substitution(lesson, _) | TimetableElement::Cancelled(lesson) => lesson,
+                TimetableElement::Lesson(_) | TimetableElement::Event(_) => continue
+            };
+            let classes = match (lesson.classes.is_empty(), lesson.student_groups.is_empty()) {
+                (false, _) => lesson.classes.clone(),
+                (true, false) => lesson.student_groups.clone(),
+                (true, true) => vec![String::new()]
+            };
+            let day = plan.map.entry(date).or_default();
+            for class in classes {
+                check_treemap(day.entry(class).or_default(), hour, tte.clone());
+            }
+        }
+        Ok(plan)
+    }
+
+    /// The plan of a single day, e.g. for showing today and tomorrow on separate screens
+    pub fn day(&self, date: NaiveDate) -> Option<&BTreeMap<String, BTreeMap<usize, Vec<TimetableElement>>>> {
+        self.map.get(&date)
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -188,6 +405,9 @@ pub struct Weekdays {
 }
 impl Weekdays {
     pub fn from_interna(interna_timetable: Result) -> std::result::Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::from_daymap(DayMap::from_interna(interna_timetable)?))
+    }
+    pub fn from_daymap(daymap: DayMap) -> Self {
         let mut week = Self {
             monday: BTreeMap::new(),
             tuesday: BTreeMap::new(),
@@ -195,30 +415,22 @@ impl Weekdays {
             thursday: BTreeMap::new(),
             friday: BTreeMap::new()
         };
-        for ilesson in interna_timetable {
-            #[allow(unused_assignments)]
-            let mut tte: Option<TimetableElement> = None;
-            if ilesson.is_new.is_some() {
-                tte = Some(TimetableElement::Event(Event::new(skip_none!(ilesson.event))))
-            } else if ilesson.is_substitution.is_some() {
-                tte = Some(TimetableElement::Substitution(Lesson::from_actual(skip_none!(ilesson.actual_lesson), ilesson.comment.clone()), skip_none!(Lesson::from_orig_vec(skip_none!(&ilesson.original_lessons), ilesson.comment))));
-            } else if ilesson.is_cancelled.is_some() {
-                tte = Some(TimetableElement::Cancelled(skip_none!(Lesson::from_orig_vec(skip_none!(&ilesson.original_lessons), ilesson.comment.clone()))));
-            } else {
-                tte = Some(TimetableElement::Lesson(Lesson::from_actual(skip_none!(ilesson.actual_lesson), ilesson.comment.clone())));
-            }
-            let date = NaiveDate::parse_from_str(&ilesson.date, "%F")?;
-            match date.weekday() {
-                Weekday::Mon => check_treemap(&mut week.monday, ilesson.class_hour.number.parse()?, skip_none!(tte)),
-                Weekday::Tue => check_treemap(&mut week.tuesday, ilesson.class_hour.number.parse()?, skip_none!(tte)),
-                Weekday::Wed => check_treemap(&mut week.wednesday, ilesson.class_hour.number.parse()?, skip_none!(tte)),
-                Weekday::Thu => check_treemap(&mut week.thursday, ilesson.class_hour.number.parse()?, skip_none!(tte)),
-                Weekday::Fri => check_treemap(&mut week.friday, ilesson.class_hour.number.parse()?, skip_none!(tte)),
+        for (date, day) in daymap.map {
+            let target = match date.weekday() {
+                Weekday::Mon => &mut week.monday,
+                Weekday::Tue => &mut week.tuesday,
+                Weekday::Wed => &mut week.wednesday,
+                Weekday::Thu => &mut week.thursday,
+                Weekday::Fri => &mut week.friday,
                 _ => {
                     eprintln!("The \"smart\" representation does not suport lessons on sat/sun");
+                    continue;
                 }
             };
+            for (hour, elements) in day {
+                target.entry(hour).or_default().extend(elements);
+            }
         }
-        Ok(week)
+        week
     }
 }